@@ -1,6 +1,8 @@
 // This file is required for napi-rs to build the native addon
+#[cfg(feature = "napi")]
 extern crate napi_build;
 
 fn main() {
+    #[cfg(feature = "napi")]
     napi_build::setup();
 }
@@ -3,6 +3,31 @@
 
 /* auto-generated by NAPI-RS */
 
+/** Options for creating an MCP manager */
+export interface ManagerOptions {
+  /** Directory for per-server log files; nothing is written to disk when omitted */
+  logDir?: string
+  /** Size in bytes at which a server's log file is rotated (default 5 MiB) */
+  maxLogFileSize?: number
+  /** Number of rotated log files kept per server (default 3) */
+  maxLogFiles?: number
+  /** Whether JSON-RPC messages are written to the log files as well */
+  logTraffic?: boolean
+  /** Number of recent events kept across all servers (default 1000) */
+  eventBufferSize?: number
+  /** Number of recent events kept for each server (default 200) */
+  serverEventBufferSize?: number
+  /** Number of JSON-RPC messages kept for each traced server (default 1000) */
+  traceBufferSize?: number
+  /**
+   * Regular expressions of secrets masked in events, traces and log files, on top of the built-in ones
+   *
+   * A `secret` capture group limits the mask to that part of the match.
+   */
+  redactionPatterns?: Array<string>
+  /** Whether the built-in patterns for bearer tokens, cloud and API keys are applied (default true) */
+  defaultRedactionPatterns?: boolean
+}
 /** Configuration for starting an MCP server */
 export interface ServerConfig {
   /** Unique identifier for the server */
@@ -11,6 +36,215 @@ export interface ServerConfig {
   command: string
   /** Arguments to pass to the command */
   args: Array<string>
+  /**
+   * Environment variables set for the server process, on top of the inherited ones
+   *
+   * Values may contain `${env:NAME}`, `${file:/path}` and `${secret:key}` references,
+   * resolved when the process is spawned and masked wherever the manager reports text.
+   * Values of variables whose names suggest a secret, such as `API_KEY`, are masked too.
+   */
+  env?: Record<string, string>
+  /** Minimum level of MCP log messages requested from the server once it started */
+  logLevel?: string
+  /** Regular expression with a `level` capture group that extracts the log level from stderr lines */
+  logLevelPattern?: string
+  /** Prefix for this server's tool names under the `customPrefix` namespacing policy */
+  toolPrefix?: string
+  /** Glob patterns of tools to expose; all tools are exposed when omitted */
+  includeTools?: Array<string>
+  /** Glob patterns of tools to hide, applied after `includeTools` */
+  excludeTools?: Array<string>
+  /** Map of original tool name to the name it is exposed as */
+  aliases?: Record<string, string>
+  /** Whether every JSON-RPC message exchanged with the server is traced */
+  trace?: boolean
+  /** File the JSON-RPC exchange with the server is recorded to (JSON Lines) */
+  record?: string
+  /** Recording to play back instead of starting `command` */
+  replay?: ReplayConfig
+  /** Milliseconds to wait for tool results; calls wait indefinitely when omitted */
+  toolTimeoutMs?: number
+  /** Restrictions applied to the server process (Linux only) */
+  sandbox?: SandboxConfig
+  /** Memory and CPU usage at which the server is warned about, restarted or stopped */
+  limits?: ResourceLimits
+}
+/**
+ * Memory and CPU usage limits of a server, measured over its whole process tree
+ *
+ * Reaching a soft limit emits a `resourceLimit` warning; reaching a hard limit
+ * also restarts or stops the server.
+ */
+export interface ResourceLimits {
+  /** Resident memory in megabytes at which a warning is emitted */
+  softMemoryMb?: number
+  /** Resident memory in megabytes at which the hard limit action is taken */
+  hardMemoryMb?: number
+  /** CPU usage in percent of one core at which, once sustained, a warning is emitted */
+  softCpuPercent?: number
+  /** CPU usage in percent of one core at which, once sustained, the hard limit action is taken */
+  hardCpuPercent?: number
+  /** Seconds CPU usage must stay at or above a CPU limit to reach it (default 60) */
+  cpuWindowSecs?: number
+  /** Milliseconds between measurements (default 5000) */
+  checkIntervalMs?: number
+  /** What happens when a hard limit is reached (default `restart`) */
+  action?: LimitAction
+  /**
+   * Milliseconds a server stopped for reaching a hard limit gets to exit once its input is
+   * closed, and again after SIGTERM, before it is killed (default 2000)
+   */
+  gracePeriodMs?: number
+  /**
+   * Restarts for reaching a hard limit allowed within `restartWindowSecs`; once used up the
+   * server is stopped instead (default 3)
+   */
+  maxRestarts?: number
+  /** Seconds over which restarts are counted against `maxRestarts` (default 300) */
+  restartWindowSecs?: number
+}
+/** What happens to a server that reaches a hard resource limit */
+export const enum LimitAction {
+  /** Stop the server and start it again with the same configuration */
+  Restart = 'restart',
+  /** Stop the server */
+  Stop = 'stop'
+}
+/** Resource a limit applies to */
+export const enum LimitedResource {
+  /** Resident memory, in megabytes */
+  Memory = 'memory',
+  /** Sustained CPU usage, in percent of one core */
+  Cpu = 'cpu'
+}
+/** Which of a resource's limits was reached */
+export const enum LimitLevel {
+  /** Only a warning is emitted */
+  Soft = 'soft',
+  /** The server is restarted or stopped */
+  Hard = 'hard'
+}
+/** A resource limit a server reached */
+export interface LimitBreach {
+  /** Resource that reached its limit */
+  resource: LimitedResource
+  /** Whether the soft or the hard limit was reached */
+  level: LimitLevel
+  /** Measured usage, in megabytes or percent of one core */
+  value: number
+  /** Limit that was reached, in the same unit */
+  limit: number
+  /** Unix timestamp in milliseconds */
+  timestamp: number
+}
+/**
+ * Restrictions applied to a server process before it runs
+ *
+ * Only supported on Linux; starting a sandboxed server elsewhere fails.
+ */
+export interface SandboxConfig {
+  /** Maximum size of the address space in megabytes (`RLIMIT_AS`) */
+  maxMemoryMb?: number
+  /** Maximum number of open file descriptors (`RLIMIT_NOFILE`) */
+  maxOpenFiles?: number
+  /** Seconds of CPU time after which the process is killed (`RLIMIT_CPU`) */
+  maxCpuSeconds?: number
+  /** Maximum number of processes of the user the server runs as (`RLIMIT_NPROC`) */
+  maxProcesses?: number
+  /**
+   * Whether the process may gain privileges through setuid programs (default false)
+   *
+   * Ignored when `readPaths` or `writePaths` is set, as Landlock requires `no_new_privs`.
+   */
+  allowNewPrivileges?: boolean
+  /**
+   * Paths the server may read and execute below; restricts the filesystem with Landlock
+   *
+   * The command itself and the system directories needed to run programs are always readable.
+   */
+  readPaths?: Array<string>
+  /** Paths the server may read and write below; restricts the filesystem with Landlock */
+  writePaths?: Array<string>
+  /** Network access of the server (default `host`) */
+  network?: SandboxNetwork
+}
+/** Network access of a sandboxed server */
+export const enum SandboxNetwork {
+  /** Use the network of the host */
+  Host = 'host',
+  /**
+   * Run in a new user and network namespace without any interface but a downed loopback
+   *
+   * When namespaces are unavailable the server runs with host networking and a warning is emitted.
+   */
+  None = 'none'
+}
+/** A running server as reported by `listServers` */
+export interface ServerInfo {
+  /** ID of the server */
+  serverId: string
+  /** Configuration the server was started with; env values still hold their secret references */
+  config: ServerConfig
+  /** PID of the server process, unless a recording is played back */
+  pid?: number
+  /** Hash of the configuration, as reported by `apply` */
+  configHash: string
+  /** Number of tools the server exposes */
+  toolCount: number
+  /** Number of times the server was restarted for reaching a hard resource limit */
+  limitRestarts: number
+  /** Most recent resource limit the server reached, kept across restarts */
+  lastLimitBreach?: LimitBreach
+}
+/** Resource limits a server reached, as reported by `getLimitStatus` */
+export interface LimitStatus {
+  /** ID of the server */
+  serverId: string
+  /** Number of times the server was restarted for reaching a hard resource limit */
+  restarts: number
+  /** Most recent resource limit the server reached */
+  lastBreach?: LimitBreach
+  /** Whether a hard limit stopped the server, which was not started again since */
+  stopped: boolean
+}
+/** Resource usage of a server, summed over its process and all its descendants */
+export interface ServerStats {
+  /** PID of the server process */
+  pid: number
+  /** CPU usage in percent of one core, averaged since the previous measurement */
+  cpuPercent: number
+  /** Resident memory in bytes */
+  memoryBytes: number
+  /** Virtual memory in bytes */
+  virtualMemoryBytes: number
+  /** Number of threads */
+  threadCount: number
+  /** Number of open file descriptors, unless the platform does not report them */
+  openFiles?: number
+  /** Number of descendant processes */
+  childCount: number
+}
+/** Playback of a recorded session in place of a real server */
+export interface ReplayConfig {
+  /** Recording file written through `record` */
+  path: string
+  /** How requests are matched against recorded ones (default `exact`) */
+  matcher?: ReplayMatcher
+  /** JSON pointers into request params that are ignored when matching, e.g. `/arguments/timestamp` */
+  ignoreParams?: Array<string>
+}
+/**
+ * How replayed requests are matched against recorded ones
+ *
+ * `initialize` and `ping` always match by method alone.
+ */
+export const enum ReplayMatcher {
+  /** Same method and equal params */
+  Exact = 'exact',
+  /** Same method, with every recorded param present and equal */
+  Subset = 'subset',
+  /** Same method, whatever the params */
+  Method = 'method'
 }
 /** Information about a tool provided by an MCP server */
 export interface ToolInfo {
@@ -18,10 +252,128 @@ export interface ToolInfo {
   serverId: string
   /** Name of the tool */
   toolName: string
+  /** Name of the tool, unique across all servers, according to the namespacing policy */
+  qualifiedName: string
   /** Description of the tool */
   description: string
   /** Parameters accepted by the tool (as a JSON string) */
   parameters: string
+  /** Hints the server gives about the tool's behaviour */
+  annotations?: ToolAnnotations
+}
+/** Hints about a tool's behaviour, as reported by its server */
+export interface ToolAnnotations {
+  /** Human-readable title of the tool */
+  title?: string
+  /** Whether the tool does not modify its environment */
+  readOnlyHint?: boolean
+  /** Whether the tool may perform destructive updates */
+  destructiveHint?: boolean
+  /** Whether repeated calls with the same arguments have no additional effect */
+  idempotentHint?: boolean
+  /** Whether the tool interacts with external entities */
+  openWorldHint?: boolean
+}
+/** A resource offered by an MCP server */
+export interface ResourceInfo {
+  /** ID of the server that provides this resource */
+  serverId: string
+  /** URI identifying the resource */
+  uri: string
+  /** Name of the resource */
+  name: string
+  /** Description of the resource */
+  description?: string
+  /** MIME type of the resource content */
+  mimeType?: string
+}
+/** A prompt template offered by an MCP server */
+export interface PromptInfo {
+  /** ID of the server that provides this prompt */
+  serverId: string
+  /** Name of the prompt */
+  name: string
+  /** Description of the prompt */
+  description?: string
+  /** Arguments the prompt accepts */
+  arguments: Array<PromptArgumentInfo>
+}
+/** An argument of a prompt template */
+export interface PromptArgumentInfo {
+  /** Name of the argument */
+  name: string
+  /** Description of the argument */
+  description?: string
+  /** Whether the argument must be given */
+  required: boolean
+}
+/** How tool names are made unique across servers */
+export const enum NamespacePolicy {
+  /** Prefix every tool with the ID of its server (`serverId__toolName`) */
+  ServerId = 'serverId',
+  /** Prefix every tool with its server's `toolPrefix`, falling back to the server ID */
+  CustomPrefix = 'customPrefix',
+  /** Keep tool names as-is; on collision the server started first wins */
+  FirstWins = 'firstWins'
+}
+/** LLM provider formats that tools can be exported as */
+export const enum ToolFormat {
+  /** OpenAI chat completions `tools` entries */
+  OpenAI = 'openai',
+  /** Anthropic messages `tools` entries */
+  Anthropic = 'anthropic',
+  /** Gemini `functionDeclarations` entries */
+  Gemini = 'gemini'
+}
+/** Tool definition ready to be sent to an LLM provider */
+export interface ProviderTool {
+  /** Provider-safe name of the tool, unique across all servers */
+  name: string
+  /** ID of the server that provides this tool */
+  serverId: string
+  /** Name of the tool on its server */
+  toolName: string
+  /** Tool definition in the provider's format (as a JSON string) */
+  definition: string
+}
+/** Options for `apply` */
+export interface ApplyOptions {
+  /** Maximum number of servers started or stopped at the same time (default 4) */
+  concurrency?: number
+}
+/** Options for `watchConfig` */
+export interface ConfigWatchOptions {
+  /** Milliseconds the file must stay unchanged before it is reloaded (default 300) */
+  debounceMs?: number
+}
+/** Options for `watchStats` */
+export interface StatsWatchOptions {
+  /** Milliseconds between `serverStats` events (default 5000) */
+  intervalMs?: number
+}
+/** What `apply` did with a server */
+export const enum ApplyAction {
+  /** The server was not running and was started */
+  Started = 'started',
+  /** The server is no longer wanted and was stopped */
+  Stopped = 'stopped',
+  /** The server's configuration changed, so it was stopped and started again */
+  Restarted = 'restarted',
+  /** The server already runs with the same configuration */
+  Unchanged = 'unchanged',
+  /** Starting or stopping the server failed */
+  Failed = 'failed'
+}
+/** Outcome of `apply` for one server */
+export interface ApplyResult {
+  /** ID of the server */
+  serverId: string
+  /** What was done with the server */
+  action: ApplyAction
+  /** Hash of the configuration the server now runs with, if it runs */
+  configHash?: string
+  /** Why the server could not be started or stopped */
+  error?: string
 }
 /** Request to execute a tool on an MCP server */
 export interface ToolExecutionRequest {
@@ -31,13 +383,81 @@ export interface ToolExecutionRequest {
   toolName: string
   /** Input parameters for the tool (as a JSON string) */
   inputs: string
+  /** Token the server's progress events for this call will carry */
+  progressToken?: string
+  /** Milliseconds to wait for the result, overriding the server's `toolTimeoutMs` */
+  timeoutMs?: number
 }
-/** Event emitted by the MCP manager */
-export interface McpEvent {
-  /** Type of event */
-  eventType: string
-  /** Event payload (as a JSON string) */
-  payload: string
+/** Whether calls to a tool need approval */
+export const enum ApprovalPolicy {
+  /** Run the tool without asking */
+  AlwaysAllow = 'alwaysAllow',
+  /** Ask the approval handler before every call */
+  AlwaysAsk = 'alwaysAsk',
+  /** Refuse every call */
+  Never = 'never'
+}
+/** Tool call awaiting approval */
+export interface ApprovalRequest {
+  /** ID of the server the tool runs on */
+  serverId: string
+  /** Name of the tool to execute */
+  toolName: string
+  /** Arguments the tool will be called with (as a JSON string) */
+  arguments: string
+  /** Hints the server gives about the tool's behaviour */
+  annotations?: ToolAnnotations
+}
+/** Answer of the approval handler to an approval request */
+export interface ApprovalDecision {
+  /** Whether the call may proceed */
+  approved: boolean
+  /** Replacement arguments for the call (as a JSON string) */
+  arguments?: string
+  /** Reason given for denying the call */
+  reason?: string
+}
+/** Tool call passed to JavaScript interceptors */
+export interface InterceptedCall {
+  /** ID of the server the tool runs on */
+  serverId: string
+  /** Name of the tool to execute */
+  toolName: string
+  /** Arguments the tool will be called with (as a JSON string) */
+  arguments: string
+}
+/** Tool call result passed to JavaScript interceptors */
+export interface InterceptedResult {
+  /** ID of the server the tool ran on */
+  serverId: string
+  /** Name of the tool that was executed */
+  toolName: string
+  /** Arguments the tool was called with (as a JSON string) */
+  arguments: string
+  /** Result of the call (as a JSON string) */
+  result: string
+}
+/** Filter for querying recently emitted events */
+export interface EventQuery {
+  /** Only events of this server */
+  serverId?: string
+  /** Only events of these types */
+  eventTypes?: Array<McpEventType>
+  /** Only log events at or above this level; other events are not affected */
+  minLevel?: string
+  /** Only events with a sequence number greater than this */
+  sinceSequence?: number
+  /** Return at most this many of the newest matching events */
+  limit?: number
+}
+/** Selects the events delivered to a subscriber */
+export interface EventFilter {
+  /** Only events of these types */
+  eventTypes?: Array<McpEventType>
+  /** Only events of these servers */
+  serverIds?: Array<string>
+  /** Only log events at or above this level; other events are not affected */
+  minLevel?: string
 }
 /** Log event emitted by the MCP manager */
 export interface LogEvent {
@@ -47,25 +467,273 @@ export interface LogEvent {
   message: string
   /** Server ID (if applicable) */
   serverId?: string
+  /** Name of the logger that produced the entry (for MCP log messages) */
+  logger?: string
+  /** Structured data of the entry (for MCP log messages, as a JSON string) */
+  data?: string
+  /** Time the entry was logged, in milliseconds since the Unix epoch */
+  timestamp: number
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Server started event emitted by the MCP manager */
+export interface ServerStartedEvent {
+  /** ID of the server */
+  serverId: string
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Server stopped event emitted by the MCP manager */
+export interface ServerStoppedEvent {
+  /** ID of the server */
+  serverId: string
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Server crashed event emitted by the MCP manager */
+export interface ServerCrashedEvent {
+  /** ID of the server */
+  serverId: string
+  /** What is known about why the server went away */
+  reason: string
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
 }
 /** Tools updated event emitted by the MCP manager */
 export interface ToolsUpdatedEvent {
+  /** ID of the server whose tools changed */
+  serverId: string
   /** List of available tools */
   tools: Array<ToolInfo>
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Progress event emitted by the MCP manager */
+export interface ProgressEvent {
+  /** ID of the server reporting progress */
+  serverId: string
+  /** Progress token of the tool call, as given in the execution request */
+  progressToken: string
+  /** Progress so far */
+  progress: number
+  /** Total progress required, if known */
+  total?: number
+  /** Description of the current progress */
+  message?: string
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Config reloaded event emitted by the MCP manager */
+export interface ConfigReloadedEvent {
+  /** Path of the config file */
+  path: string
+  /** Servers that were started, stopped, restarted or failed; unchanged ones are left out */
+  changes: Array<ApplyResult>
+  /** Why the file was rejected; servers are left untouched when set */
+  error?: string
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Server stats event emitted by the MCP manager */
+export interface ServerStatsEvent {
+  /** ID of the server */
+  serverId: string
+  /** Resource usage of the server's process tree */
+  stats: ServerStats
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Resource limit event emitted by the MCP manager */
+export interface ResourceLimitEvent {
+  /** ID of the server */
+  serverId: string
+  /** Limit that was reached */
+  breach: LimitBreach
+  /** What is done about it; only set for hard limits */
+  action?: LimitAction
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Direction of a JSON-RPC message relative to the manager */
+export const enum Direction {
+  /** Sent by the manager to the server */
+  Outgoing = 'outgoing',
+  /** Received by the manager from the server */
+  Incoming = 'incoming'
+}
+/** Kind of a JSON-RPC message */
+export const enum MessageKind {
+  /** Request expecting a response */
+  Request = 'request',
+  /** Successful response to a request */
+  Response = 'response',
+  /** Error response to a request */
+  Error = 'error',
+  /** Notification without a response */
+  Notification = 'notification'
+}
+/** JSON-RPC message captured while tracing a server */
+export interface TraceEntry {
+  /** ID of the server the message was exchanged with */
+  serverId: string
+  /** Whether the message was sent or received */
+  direction: Direction
+  /** Kind of the message */
+  kind: MessageKind
+  /** Method of the request or notification, or of the request a response answers */
+  method?: string
+  /** JSON-RPC ID of the request or response (as a JSON string) */
+  id?: string
+  /** The message itself (as a JSON string) */
+  message: string
+  /** Time the message passed the transport, in milliseconds since the Unix epoch */
+  timestamp: number
+  /** For responses, milliseconds since the matching request passed the transport */
+  latencyMs?: number
+  /** Position of the event among all events of its manager, increasing monotonically */
+  sequence: number
+}
+/** Filter for querying a server's trace */
+export interface TraceQuery {
+  /** Only messages of these methods */
+  methods?: Array<string>
+  /** Only messages with a sequence number greater than this */
+  sinceSequence?: number
+  /** Return at most this many of the newest matching messages */
+  limit?: number
+}
+/** File format for exported traces */
+export const enum TraceFormat {
+  /** HTTP Archive layout with one entry per request/response pair */
+  Har = 'har',
+  /** Request history and notifications as shown by the MCP Inspector */
+  Inspector = 'inspector'
 }
 export type McpManagerWrapper = McpManager
 /** NAPI wrapper for the MCP manager */
 export declare class McpManager {
   /** Create a new MCP manager wrapper */
-  constructor(eventCallback: (err: Error | null, arg: McpEvent) => any)
+  constructor(eventCallback: (err: Error | null, arg: McpEvent) => any, options?: ManagerOptions | undefined | null)
   /** Start a new MCP server */
   start(config: ServerConfig): Promise<void>
+  /** Start the servers defined by a `claude_desktop_config.json`-style file, given as a path or JSON, and stop all others */
+  startFromConfig(source: string): Promise<Array<ApplyResult>>
+  /** Start, stop and restart servers so exactly the given ones run, reporting the outcome per server */
+  apply(configs: Array<ServerConfig>, options?: ApplyOptions | undefined | null): Promise<Array<ApplyResult>>
+  /** Start the servers defined by a config file and reload it whenever it changes, emitting `configReloaded` events */
+  watchConfig(path: string, options?: ConfigWatchOptions | undefined | null): Promise<Array<ApplyResult>>
+  /** Stop watching the config file passed to `watchConfig`; running servers are kept */
+  unwatchConfig(): void
   /** Stop an MCP server */
   stop(serverId: string): Promise<void>
+  /**
+   * Set the minimum level of MCP log messages a server sends
+   *
+   * Accepts the MCP levels (`debug`, `info`, `notice`, `warning`, `error`,
+   * `critical`, `alert`, `emergency`) as well as `warn`.
+   */
+  setLogLevel(serverId: string, level: string): Promise<void>
+  /** Get the last lines of a server's log file */
+  getLogTail(serverId: string, lines: number): Array<string>
+  /** Get recently emitted events matching a query, oldest first */
+  getRecentEvents(query?: EventQuery | undefined | null): Array<McpEvent>
+  /** Replace the event callback, replaying buffered events newer than `replaySince` first */
+  setEventCallback(callback: (err: Error | null, arg: McpEvent) => any, replaySince?: number | undefined | null): void
+  /** Get the captured JSON-RPC traffic of a server started with `trace`, oldest first */
+  getTrace(serverId: string, query?: TraceQuery | undefined | null): Array<TraceEntry>
+  /** Drop the captured JSON-RPC traffic of a server */
+  clearTrace(serverId: string): void
+  /** Write the captured JSON-RPC traffic of a server to a JSON file */
+  exportTrace(serverId: string, path: string, format: TraceFormat): void
+  /**
+   * Listen to the events passing a filter and return the subscription ID
+   *
+   * Buffered events newer than `replaySince` that pass the filter are
+   * delivered first.
+   */
+  subscribe(filter: EventFilter | undefined | null, callback: (err: Error | null, arg: McpEvent) => any, replaySince?: number | undefined | null): number
+  /** Remove an event subscription, returning whether it was registered */
+  unsubscribe(id: number): boolean
+  /** Get the running servers, in start order, with secret values in their env masked */
+  listServers(): Array<ServerInfo>
+  /** Get the resource limits a server reached, also after a hard limit stopped it */
+  getLimitStatus(serverId: string): LimitStatus | null
+  /** Measure CPU, memory, threads, open files and child processes of a server's process tree */
+  getServerStats(serverId: string): Promise<ServerStats>
+  /** Emit a `serverStats` event for every server at a fixed interval */
+  watchStats(options?: StatsWatchOptions | undefined | null): Promise<void>
+  /** Stop the `serverStats` events started by `watchStats` */
+  unwatchStats(): void
   /** Get all available tools across all servers */
   getTools(): Array<ToolInfo>
+  /**
+   * Set how tool names are made unique across servers
+   *
+   * Qualified names change with the policy, so every running server gets a
+   * `toolsUpdated` event with its tools under the new names.
+   */
+  setNamespacePolicy(policy: NamespacePolicy): void
+  /** Get the current namespacing policy */
+  getNamespacePolicy(): NamespacePolicy
+  /** Get all available tools as definitions for an LLM provider */
+  getToolsAs(format: ToolFormat): Array<ProviderTool>
+  /** Find the tool behind a provider tool name returned by `getToolsAs` */
+  resolveToolName(name: string): ToolInfo | null
+  /** List the resources a server offers */
+  listResources(serverId: string): Promise<Array<ResourceInfo>>
+  /** List the prompts a server offers */
+  listPrompts(serverId: string): Promise<Array<PromptInfo>>
   /** Execute a tool on an MCP server */
   executeTool(request: ToolExecutionRequest): Promise<string>
+  /** Execute a tool identified by its qualified name */
+  executeToolByQualifiedName(name: string, inputs: string): Promise<string>
+  /**
+   * Register a callback asked before tools whose policy is `alwaysAsk` run
+   *
+   * The callback resolves to a decision that can approve the call, deny it,
+   * or approve it with replacement arguments.
+   */
+  setApprovalHandler(callback: (err: Error | null, arg: ApprovalRequest) => Promise<ApprovalDecision>): void
+  /** Resolve `${secret:key}` references in server env values with a callback returning a promise of the value */
+  setSecretProvider(callback: (err: Error | null, arg: string) => Promise<string>): void
+  /** Resolve `${secret:key}` references from a JSON file mapping keys to values */
+  setSecretsFile(path: string): void
+  /** Remove the secret provider; servers with `${secret:key}` references fail to start */
+  clearSecretProvider(): void
+  /** Remove the approval callback; calls to tools whose policy is `alwaysAsk` are denied until another is registered */
+  clearApprovalHandler(): void
+  /** Set whether calls to a tool need approval */
+  setToolApprovalPolicy(serverId: string, toolName: string, policy: ApprovalPolicy): void
+  /** Remove a tool's approval policy so the default policy applies again */
+  clearToolApprovalPolicy(serverId: string, toolName: string): void
+  /** Get the approval policy that applies to a tool */
+  getToolApprovalPolicy(serverId: string, toolName: string): ApprovalPolicy
+  /**
+   * Set the approval policy for tools without a policy of their own
+   *
+   * Until it is set, such tools are asked about while an approval handler is
+   * registered and run without asking otherwise.
+   */
+  setDefaultApprovalPolicy(policy: ApprovalPolicy): void
+  /**
+   * Add interceptor callbacks around every tool call and return the interceptor's ID
+   *
+   * `before` resolves to the arguments (as a JSON string) and `after` to the
+   * result (as a JSON string). Interceptors run in the order they are added
+   * before the call, and in reverse order after it.
+   */
+  addInterceptor(before?: (err: Error | null, arg: InterceptedCall) => Promise<string>, after?: (err: Error | null, arg: InterceptedResult) => Promise<string>): number
+  /**
+   * Fill in missing arguments of tool calls and return the interceptor's ID
+   *
+   * Defaults are given as a JSON object string and can be limited to one
+   * server and one tool.
+   */
+  addArgumentDefaults(defaults: string, serverId?: string | undefined | null, toolName?: string | undefined | null): number
+  /** Truncate text content of tool results and return the interceptor's ID */
+  addResultTruncation(maxLength: number): number
+  /** Remove an interceptor, returning whether it was registered */
+  removeInterceptor(id: number): boolean
 }
 export declare namespace error_codes {
   export const SERVER_NOT_FOUND: string
@@ -75,5 +743,25 @@ export declare namespace error_codes {
   export const COMMUNICATION_ERROR: string
   export const TOOL_EXECUTION_ERROR: string
   export const MCP_ERROR: string
+  export const APPROVAL_DENIED: string
+  export const CONFIG_ERROR: string
   export const UNKNOWN_ERROR: string
 }
+/**
+ * Event emitted by the MCP manager
+ *
+ * Reaches TypeScript as the variant's object with an `eventType` discriminant.
+ */
+export type McpEvent =
+  | ({ eventType: 'log' } & LogEvent)
+  | ({ eventType: 'serverStarted' } & ServerStartedEvent)
+  | ({ eventType: 'serverStopped' } & ServerStoppedEvent)
+  | ({ eventType: 'serverCrashed' } & ServerCrashedEvent)
+  | ({ eventType: 'toolsUpdated' } & ToolsUpdatedEvent)
+  | ({ eventType: 'progress' } & ProgressEvent)
+  | ({ eventType: 'trace' } & TraceEntry)
+  | ({ eventType: 'configReloaded' } & ConfigReloadedEvent)
+  | ({ eventType: 'serverStats' } & ServerStatsEvent)
+  | ({ eventType: 'resourceLimit' } & ResourceLimitEvent)
+/** Discriminant of an event */
+export type McpEventType = McpEvent['eventType']
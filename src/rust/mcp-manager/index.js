@@ -310,7 +310,19 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { McpManager, error_codes } = nativeBinding
+const { NamespacePolicy, ToolFormat, ApprovalPolicy, ApplyAction, ReplayMatcher, SandboxNetwork, LimitAction, LimitedResource, LimitLevel, Direction, MessageKind, TraceFormat, McpManager, error_codes } = nativeBinding
 
+module.exports.NamespacePolicy = NamespacePolicy
+module.exports.ToolFormat = ToolFormat
+module.exports.ApprovalPolicy = ApprovalPolicy
+module.exports.ApplyAction = ApplyAction
+module.exports.ReplayMatcher = ReplayMatcher
+module.exports.SandboxNetwork = SandboxNetwork
+module.exports.LimitAction = LimitAction
+module.exports.LimitedResource = LimitedResource
+module.exports.LimitLevel = LimitLevel
+module.exports.Direction = Direction
+module.exports.MessageKind = MessageKind
+module.exports.TraceFormat = TraceFormat
 module.exports.McpManager = McpManager
 module.exports.error_codes = error_codes
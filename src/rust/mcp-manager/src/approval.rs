@@ -0,0 +1,117 @@
+use crate::error::{Error, Result};
+use crate::models::{ApprovalDecision, ApprovalPolicy, ApprovalRequest};
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use std::sync::{Arc, RwLock};
+
+/// Callback deciding whether a tool call may proceed
+pub type ApprovalHandler =
+    Arc<dyn Fn(ApprovalRequest) -> BoxFuture<'static, Result<ApprovalDecision>> + Send + Sync>;
+
+/// Approval policies and the handler consulted before tool calls
+pub struct ApprovalGate {
+    /// Handler asked about calls whose policy is `AlwaysAsk`
+    handler: RwLock<Option<ApprovalHandler>>,
+    /// Policies for individual tools, keyed by server ID and exposed tool name
+    policies: DashMap<(String, String), ApprovalPolicy>,
+    /// Policy for tools without a policy of their own, once one is set
+    default_policy: RwLock<Option<ApprovalPolicy>>,
+}
+
+impl ApprovalGate {
+    /// Create a gate that asks about every call once a handler is registered
+    pub fn new() -> Self {
+        Self {
+            handler: RwLock::new(None),
+            policies: DashMap::new(),
+            default_policy: RwLock::new(None),
+        }
+    }
+
+    /// Register the handler asked about tool calls, replacing any previous one
+    pub fn set_handler(&self, handler: Option<ApprovalHandler>) {
+        *self.handler.write().unwrap() = handler;
+    }
+
+    /// Set the policy for a single tool
+    pub fn set_policy(&self, server_id: &str, tool_name: &str, policy: ApprovalPolicy) {
+        self.policies
+            .insert((server_id.to_string(), tool_name.to_string()), policy);
+    }
+
+    /// Remove the policy for a single tool so the default applies again
+    pub fn clear_policy(&self, server_id: &str, tool_name: &str) {
+        self.policies
+            .remove(&(server_id.to_string(), tool_name.to_string()));
+    }
+
+    /// Set the policy for tools without a policy of their own
+    pub fn set_default_policy(&self, policy: ApprovalPolicy) {
+        *self.default_policy.write().unwrap() = Some(policy);
+    }
+
+    /// Get the policy that applies to a tool
+    ///
+    /// Until a default policy is set, tools without a policy of their own are
+    /// asked about while a handler is registered and allowed otherwise.
+    pub fn policy(&self, server_id: &str, tool_name: &str) -> ApprovalPolicy {
+        self.policies
+            .get(&(server_id.to_string(), tool_name.to_string()))
+            .map(|policy| *policy)
+            .or(*self.default_policy.read().unwrap())
+            .unwrap_or_else(|| match *self.handler.read().unwrap() {
+                Some(_) => ApprovalPolicy::AlwaysAsk,
+                None => ApprovalPolicy::AlwaysAllow,
+            })
+    }
+
+    /// Decide whether a tool call may proceed
+    ///
+    /// Returns the arguments to call the tool with, which the handler may have
+    /// modified. Calls that need approval are denied when no handler is
+    /// registered to give it.
+    pub async fn check(&self, request: ApprovalRequest) -> Result<String> {
+        let policy = self.policy(&request.server_id, &request.tool_name);
+        let handler = match policy {
+            ApprovalPolicy::AlwaysAllow => return Ok(request.arguments),
+            ApprovalPolicy::Never => {
+                return Err(Error::ApprovalDenied(format!(
+                    "Tool '{}' on server '{}' is not allowed to run",
+                    request.tool_name, request.server_id
+                )))
+            }
+            ApprovalPolicy::AlwaysAsk => match self.handler.read().unwrap().clone() {
+                Some(handler) => handler,
+                None => {
+                    return Err(Error::ApprovalDenied(format!(
+                        "Tool '{}' on server '{}' needs approval, but no approval handler is registered",
+                        request.tool_name, request.server_id
+                    )))
+                }
+            },
+        };
+
+        let server_id = request.server_id.clone();
+        let tool_name = request.tool_name.clone();
+        let original_arguments = request.arguments.clone();
+        let decision = handler(request).await?;
+
+        if !decision.approved {
+            return Err(Error::ApprovalDenied(match decision.reason {
+                Some(reason) => format!(
+                    "Tool '{}' on server '{}' was denied: {}",
+                    tool_name, server_id, reason
+                ),
+                None => format!("Tool '{}' on server '{}' was denied", tool_name, server_id),
+            }));
+        }
+
+        Ok(decision.arguments.unwrap_or(original_arguments))
+    }
+}
+
+impl Default for ApprovalGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,528 @@
+//! Command-line tool for driving an MCP server through the manager
+//!
+//! Reproduces what the app does with a server without Electron around it:
+//!
+//! ```text
+//! mcp-manager tools -- npx -y @modelcontextprotocol/server-everything
+//! mcp-manager call --config server.json echo '{"message":"hi"}'
+//! mcp-manager logs --level warn -- ./my-server --port 0
+//! mcp-manager repl --config server.json
+//! ```
+//!
+//! Build with `cargo build --no-default-features --features cli --bin mcp-manager`.
+//!
+//! Exit codes:
+//!
+//! | Code | Meaning                                   |
+//! |------|-------------------------------------------|
+//! | 0    | Success                                   |
+//! | 1    | Other error                               |
+//! | 2    | Invalid command line                      |
+//! | 3    | Server not found                          |
+//! | 4    | Tool not found                            |
+//! | 5    | Server process failed to start            |
+//! | 6    | Server process failed to stop             |
+//! | 7    | Communication with the server failed      |
+//! | 8    | Tool execution failed or returned `isError` |
+//! | 9    | MCP protocol error                        |
+//! | 10   | Tool call denied                          |
+//! | 11   | Invalid configuration                     |
+
+#[cfg(feature = "napi")]
+compile_error!("the CLI runs outside Node.js; build it with `--no-default-features --features cli`");
+
+use clap::{Args, Parser, Subcommand};
+use mcp_manager::{
+    parse_server_definitions, Error, EventFilter, EventQuery, LogEvent, ManagerOptions, McpEvent,
+    McpManager, Result, ServerConfig, ToolExecutionRequest,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+
+/// Server ID used when neither the config nor `--id` names one
+const DEFAULT_SERVER_ID: &str = "server";
+
+#[derive(Parser)]
+#[command(
+    name = "mcp-manager",
+    version,
+    about = "Start an MCP server and talk to it from the terminal"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the server's tools
+    Tools {
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// List the server's resources
+    Resources {
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// List the server's prompts
+    Prompts {
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// Call a tool and print its result
+    Call {
+        /// Name of the tool
+        tool: String,
+        /// Arguments as a JSON object
+        #[arg(default_value = "{}")]
+        arguments: String,
+        /// Milliseconds to wait for the result
+        #[arg(long)]
+        timeout_ms: Option<u32>,
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// Print the server's log until it exits or Ctrl-C is pressed
+    Logs {
+        /// Minimum level of the printed entries
+        #[arg(long, default_value = "trace")]
+        level: String,
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// Run commands against the server interactively
+    Repl {
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+}
+
+/// How to start the server
+#[derive(Args)]
+struct ServerArgs {
+    /// JSON file with the server configuration, in the shape passed to `start` or as an `mcpServers` block
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// Server ID, overriding the one in the configuration
+    #[arg(long)]
+    id: Option<String>,
+    /// Minimum level of MCP log messages requested from the server
+    #[arg(long)]
+    log_level: Option<String>,
+    /// Print the server's log to stderr
+    #[arg(short, long)]
+    verbose: bool,
+    /// Print results as JSON
+    #[arg(long)]
+    json: bool,
+    /// Command starting the server and its arguments, overriding the configuration
+    #[arg(last = true)]
+    command: Vec<String>,
+}
+
+impl ServerArgs {
+    /// Build the server configuration from the config file and the overrides
+    fn server_config(&self) -> Result<ServerConfig> {
+        let mut config = match &self.config {
+            Some(path) => self.read_config(path)?,
+            None => ServerConfig::default(),
+        };
+
+        if let Some((command, args)) = self.command.split_first() {
+            config.command = command.clone();
+            config.args = args.to_vec();
+        }
+        if let Some(id) = &self.id {
+            config.id = id.clone();
+        }
+        if config.id.is_empty() {
+            config.id = DEFAULT_SERVER_ID.to_string();
+        }
+        if self.log_level.is_some() {
+            config.log_level = self.log_level.clone();
+        }
+
+        if config.command.is_empty() && config.replay.is_none() {
+            return Err(Error::ConfigError(
+                "No server command; pass --config or the command after `--`".to_string(),
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Read a single server configuration, or pick one from an `mcpServers` file by `--id`
+    fn read_config(&self, path: &Path) -> Result<ServerConfig> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::ConfigError(format!("Failed to read '{}': {}", path.display(), e))
+        })?;
+        let value: Value = serde_json::from_str(&content).map_err(|e| {
+            Error::ConfigError(format!("Invalid config '{}': {}", path.display(), e))
+        })?;
+        if value.get("mcpServers").is_none() && value.get("servers").is_none() {
+            return serde_json::from_value(value).map_err(|e| {
+                Error::ConfigError(format!("Invalid config '{}': {}", path.display(), e))
+            });
+        }
+
+        let origin = format!("config '{}'", path.display());
+        let definitions = parse_server_definitions(&content, &origin)?;
+        let mut servers = definitions.servers;
+        match &self.id {
+            Some(id) => {
+                if let Some((_, reason)) = definitions.skipped.iter().find(|(skipped, _)| skipped == id) {
+                    return Err(Error::ConfigError(format!("Server '{}' cannot run: {}", id, reason)));
+                }
+                servers
+                    .into_iter()
+                    .find(|server| server.id == *id)
+                    .ok_or_else(|| Error::ServerNotFound(format!("No server '{}' in {}", id, origin)))
+            }
+            None if servers.len() == 1 => Ok(servers.remove(0)),
+            None => {
+                let ids: Vec<&str> = servers.iter().map(|server| server.id.as_str()).collect();
+                Err(Error::ConfigError(format!(
+                    "{} defines {} servers; pick one with --id ({})",
+                    origin,
+                    ids.len(),
+                    ids.join(", ")
+                )))
+            }
+        }
+    }
+}
+
+/// A manager running one server, with the events it emits
+struct Session {
+    manager: Arc<McpManager>,
+    server_id: String,
+    events: mpsc::UnboundedReceiver<McpEvent>,
+    json: bool,
+}
+
+impl Session {
+    /// Create a manager and start the server
+    async fn start(server: &ServerArgs) -> Result<Self> {
+        let config = server.server_config()?;
+        let server_id = config.id.clone();
+
+        let (tx, events) = mpsc::unbounded_channel();
+        let verbose = server.verbose;
+        let sink = move |event: McpEvent| {
+            if verbose {
+                if let McpEvent::Log(log) = &event {
+                    eprintln!("{}", format_log(log));
+                }
+            }
+            let _ = tx.send(event);
+        };
+        let manager = McpManager::new_shared(Arc::new(sink), ManagerOptions::default())?;
+        manager.start(config).await?;
+
+        Ok(Self {
+            manager,
+            server_id,
+            events,
+            json: server.json,
+        })
+    }
+
+    /// Stop the server unless it already went away
+    async fn stop(self) -> Result<()> {
+        match self.manager.stop(&self.server_id).await {
+            Err(Error::ServerNotFound(_)) | Ok(()) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn print_tools(&self) -> Result<()> {
+        let tools = self.manager.get_tools();
+        self.print_list(&tools, |tool| {
+            (tool.tool_name.clone(), tool.description.clone())
+        })
+    }
+
+    async fn print_resources(&self) -> Result<()> {
+        let resources = self.manager.list_resources(&self.server_id).await?;
+        self.print_list(&resources, |resource| {
+            (
+                resource.uri.clone(),
+                resource.description.clone().unwrap_or_else(|| resource.name.clone()),
+            )
+        })
+    }
+
+    async fn print_prompts(&self) -> Result<()> {
+        let prompts = self.manager.list_prompts(&self.server_id).await?;
+        self.print_list(&prompts, |prompt| {
+            let arguments: Vec<String> = prompt
+                .arguments
+                .iter()
+                .map(|argument| match argument.required {
+                    true => argument.name.clone(),
+                    false => format!("[{}]", argument.name),
+                })
+                .collect();
+            (
+                format!("{} {}", prompt.name, arguments.join(" ")),
+                prompt.description.clone().unwrap_or_default(),
+            )
+        })
+    }
+
+    /// Print items as JSON or as aligned name and description columns
+    fn print_list<T: Serialize>(
+        &self,
+        items: &[T],
+        columns: impl Fn(&T) -> (String, String),
+    ) -> Result<()> {
+        if self.json {
+            return print_json(&serde_json::to_value(items).unwrap_or_default());
+        }
+        let rows: Vec<(String, String)> = items.iter().map(columns).collect();
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, description) in rows {
+            let summary = description.lines().next().unwrap_or_default();
+            println!("{:<width$}  {}", name, summary, width = width);
+        }
+        Ok(())
+    }
+
+    /// Call a tool and print the result, failing if the tool reported an error
+    async fn call(&self, tool: &str, arguments: &str, timeout_ms: Option<u32>) -> Result<()> {
+        let result = self
+            .manager
+            .execute_tool(ToolExecutionRequest {
+                server_id: self.server_id.clone(),
+                tool_name: tool.to_string(),
+                inputs: arguments.to_string(),
+                timeout_ms,
+                ..Default::default()
+            })
+            .await?;
+        let result: Value = serde_json::from_str(&result).unwrap_or(Value::String(result));
+
+        if self.json {
+            print_json(&result)?;
+        } else {
+            print_content(&result);
+        }
+        if result.get("isError") == Some(&Value::Bool(true)) {
+            return Err(Error::ToolExecutionError(format!(
+                "Tool '{}' reported an error",
+                tool
+            )));
+        }
+        Ok(())
+    }
+
+    /// Print log events until the server exits or Ctrl-C is pressed
+    async fn tail_logs(&mut self, level: &str) -> Result<()> {
+        let filter = EventFilter {
+            event_types: Some(vec!["log".to_string()]),
+            server_ids: Some(vec![self.server_id.clone()]),
+            min_level: Some(level.to_string()),
+        };
+        let print = |event: McpEvent| {
+            if let McpEvent::Log(log) = &event {
+                println!("{}", format_log(log));
+            }
+        };
+        // Replay what was logged while starting, then follow along
+        let subscription = self.manager.subscribe(filter, Arc::new(print), Some(0));
+
+        let result = loop {
+            let event = tokio::select! {
+                event = self.events.recv() => event,
+                _ = tokio::signal::ctrl_c() => break Ok(()),
+            };
+            match event {
+                None => break Ok(()),
+                Some(McpEvent::ServerCrashed(crashed)) => {
+                    break Err(Error::CommunicationError(crashed.reason));
+                }
+                Some(_) => {}
+            }
+        };
+        self.manager.unsubscribe(subscription);
+        result
+    }
+
+    /// Read commands from stdin until `quit` or end of input
+    async fn repl(&mut self) -> Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        println!("Connected to '{}'. Type `help` for commands.", self.server_id);
+
+        loop {
+            print!("mcp> ");
+            let _ = std::io::stdout().flush();
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            };
+            let Ok(Some(line)) = line else {
+                println!();
+                return Ok(());
+            };
+
+            let line = line.trim();
+            let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let result = match command {
+                "" => Ok(()),
+                "help" => {
+                    println!("tools                   List the server's tools");
+                    println!("resources               List the server's resources");
+                    println!("prompts                 List the server's prompts");
+                    println!("call <tool> [json]      Call a tool");
+                    println!("level <level>           Set the server's MCP log level");
+                    println!("events [n]              Show the last n events (default 20)");
+                    println!("quit                    Stop the server and exit");
+                    Ok(())
+                }
+                "tools" => self.print_tools().await,
+                "resources" => self.print_resources().await,
+                "prompts" => self.print_prompts().await,
+                "call" => {
+                    let (tool, arguments) = rest.trim().split_once(' ').unwrap_or((rest.trim(), "{}"));
+                    self.call(tool, arguments, None).await
+                }
+                "level" => self.manager.set_log_level(&self.server_id, rest.trim()).await,
+                "events" => {
+                    let limit = rest.trim().parse().unwrap_or(20);
+                    let query = EventQuery {
+                        server_id: Some(self.server_id.clone()),
+                        limit: Some(limit),
+                        ..Default::default()
+                    };
+                    for event in self.manager.get_recent_events(&query) {
+                        match &event {
+                            McpEvent::Log(log) => println!("{}", format_log(log)),
+                            event => println!(
+                                "{} {}",
+                                event.event_type(),
+                                serde_json::to_string(event).unwrap_or_default()
+                            ),
+                        }
+                    }
+                    Ok(())
+                }
+                "quit" | "exit" => return Ok(()),
+                command => {
+                    println!("Unknown command '{}'; type `help` for commands", command);
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                println!("error: {}", e);
+            }
+
+            // Drop events the REPL does not show so they do not pile up
+            while let Ok(event) = self.events.try_recv() {
+                if let McpEvent::ServerCrashed(crashed) = event {
+                    return Err(Error::CommunicationError(crashed.reason));
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    match run(cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+async fn run(command: Command) -> Result<()> {
+    let server = match &command {
+        Command::Tools { server }
+        | Command::Resources { server }
+        | Command::Prompts { server }
+        | Command::Call { server, .. }
+        | Command::Logs { server, .. }
+        | Command::Repl { server } => server,
+    };
+    let mut session = Session::start(server).await?;
+
+    let result = match &command {
+        Command::Tools { .. } => session.print_tools().await,
+        Command::Resources { .. } => session.print_resources().await,
+        Command::Prompts { .. } => session.print_prompts().await,
+        Command::Call {
+            tool,
+            arguments,
+            timeout_ms,
+            ..
+        } => session.call(tool, arguments, *timeout_ms).await,
+        Command::Logs { level, .. } => session.tail_logs(level).await,
+        Command::Repl { .. } => session.repl().await,
+    };
+
+    // Report the command's failure over one from stopping the server
+    let stopped = session.stop().await;
+    result.and(stopped)
+}
+
+/// Process exit code for an error, distinct per error kind
+fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::Other(_) => 1,
+        Error::ServerNotFound(_) => 3,
+        Error::ToolNotFound(_) => 4,
+        Error::ProcessStartError(_) => 5,
+        Error::ProcessStopError(_) => 6,
+        Error::CommunicationError(_) => 7,
+        Error::ToolExecutionError(_) => 8,
+        Error::McpError(_) => 9,
+        Error::ApprovalDenied(_) => 10,
+        Error::ConfigError(_) => 11,
+    }
+}
+
+fn format_log(log: &LogEvent) -> String {
+    let logger = log
+        .logger
+        .as_ref()
+        .map(|logger| format!("[{}] ", logger))
+        .unwrap_or_default();
+    format!("{:<5} {}{}", log.level.to_uppercase(), logger, log.message)
+}
+
+fn print_json(value: &Value) -> Result<()> {
+    let output = serde_json::to_string_pretty(value)
+        .map_err(|e| Error::Other(format!("Failed to format result: {}", e)))?;
+    println!("{}", output);
+    Ok(())
+}
+
+/// Print the text of a tool result, falling back to JSON for other content
+fn print_content(result: &Value) {
+    let Some(content) = result.get("content").and_then(Value::as_array) else {
+        let _ = print_json(result);
+        return;
+    };
+    for item in content {
+        match item.get("text").and_then(Value::as_str) {
+            Some(text) => println!("{}", text),
+            None => {
+                let _ = print_json(item);
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Scriptable stdio MCP server for integration tests
+//!
+//! Only built with the `test-support` feature, which the tests enable.
+//!
+//! The behaviour is read from a JSON script given with `--script <json>` or
+//! `--script-file <path>`. Without a script the server offers a single `echo`
+//! tool. Example:
+//!
+//! ```json
+//! {
+//!   "tools": [
+//!     { "name": "slow", "delayMs": 500, "text": "done" },
+//!     { "name": "work", "progress": 3 },
+//!     { "name": "boom", "crash": true, "stderr": ["Traceback (most recent call last):"] }
+//!   ],
+//!   "resources": [{ "uri": "file:///notes.txt", "name": "notes" }],
+//!   "stderrSpam": 100,
+//!   "listChangedAfterMs": 200,
+//!   "toolsAfterChange": [{ "name": "late" }]
+//! }
+//! ```
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Behaviour of the whole server
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Script {
+    /// Tools offered at startup
+    tools: Vec<MockTool>,
+    /// Resources listed by `resources/list`, as MCP resource objects
+    resources: Vec<Value>,
+    /// Prompts listed by `prompts/list`, as MCP prompt objects
+    prompts: Vec<Value>,
+    /// Lines written to stderr at startup
+    stderr: Vec<String>,
+    /// Number of numbered filler lines written to stderr at startup
+    stderr_spam: usize,
+    /// Delay before answering `initialize`
+    initialize_delay_ms: u64,
+    /// Exit on its own this long after startup
+    crash_after_ms: Option<u64>,
+    /// Exit code used whenever the server crashes
+    exit_code: i32,
+    /// Replace the tools and send `notifications/tools/list_changed` this long after startup
+    list_changed_after_ms: Option<u64>,
+    /// Tools offered after the list changed
+    tools_after_change: Vec<MockTool>,
+    /// Keep running once stdin is closed, until a signal ends the server
+    ignore_input_close: bool,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self {
+            tools: vec![MockTool::echo()],
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            stderr: Vec::new(),
+            stderr_spam: 0,
+            initialize_delay_ms: 0,
+            crash_after_ms: None,
+            exit_code: 1,
+            list_changed_after_ms: None,
+            tools_after_change: Vec::new(),
+            ignore_input_close: false,
+        }
+    }
+}
+
+/// Behaviour of one tool
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MockTool {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "default_input_schema")]
+    input_schema: Value,
+    /// Complete `CallToolResult` to answer with
+    #[serde(default)]
+    result: Option<Value>,
+    /// Text content to answer with; the arguments are echoed when neither this nor `result` is set
+    #[serde(default)]
+    text: Option<String>,
+    /// Answer with the value of this environment variable, also writing it to stderr
+    #[serde(default)]
+    env: Option<String>,
+    /// Answer with the content of this file, or with the read error as a tool error
+    #[serde(default)]
+    read_file: Option<String>,
+    /// Answer `connected` after opening a TCP connection to this address, or the error as a tool error
+    #[serde(default)]
+    connect: Option<String>,
+    /// Mark the result as a tool error
+    #[serde(default)]
+    is_error: bool,
+    /// Answer with a JSON-RPC error carrying this message instead of a result
+    #[serde(default)]
+    error: Option<String>,
+    /// Delay before answering
+    #[serde(default)]
+    delay_ms: u64,
+    /// Milliseconds of keeping a CPU core busy before answering
+    #[serde(default)]
+    busy_ms: u64,
+    /// Megabytes of memory allocated and kept for the life of the server before answering
+    #[serde(default)]
+    allocate_mb: usize,
+    /// Command started and left running as a child process before answering
+    #[serde(default)]
+    spawn: Vec<String>,
+    /// Number of progress notifications sent before answering
+    #[serde(default)]
+    progress: u32,
+    /// Exit instead of answering
+    #[serde(default)]
+    crash: bool,
+    /// Lines written to stderr when the tool is called
+    #[serde(default)]
+    stderr: Vec<String>,
+}
+
+impl MockTool {
+    fn echo() -> Self {
+        Self {
+            name: "echo".to_string(),
+            description: Some("Return the arguments as text".to_string()),
+            input_schema: default_input_schema(),
+            result: None,
+            text: None,
+            env: None,
+            read_file: None,
+            connect: None,
+            is_error: false,
+            error: None,
+            delay_ms: 0,
+            busy_ms: 0,
+            allocate_mb: 0,
+            spawn: Vec::new(),
+            progress: 0,
+            crash: false,
+            stderr: Vec::new(),
+        }
+    }
+
+    fn definition(&self) -> Value {
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "inputSchema": self.input_schema,
+        })
+    }
+}
+
+fn default_input_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+/// Serializes writes to stdout so concurrent answers never interleave
+#[derive(Clone)]
+struct Output(Arc<Mutex<std::io::Stdout>>);
+
+impl Output {
+    fn send(&self, message: Value) {
+        let mut stdout = self.0.lock().unwrap();
+        let _ = writeln!(stdout, "{}", message);
+        let _ = stdout.flush();
+    }
+}
+
+fn main() {
+    let script = match read_script() {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("mock-mcp-server: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    for line in &script.stderr {
+        eprintln!("{}", line);
+    }
+    for index in 0..script.stderr_spam {
+        eprintln!("INFO spam line {}", index);
+    }
+
+    let output = Output(Arc::new(Mutex::new(std::io::stdout())));
+    let tools = Arc::new(Mutex::new(script.tools.clone()));
+
+    if let Some(delay) = script.crash_after_ms {
+        let exit_code = script.exit_code;
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay));
+            eprintln!("ERROR mock server crashing on schedule");
+            std::process::exit(exit_code);
+        });
+    }
+
+    if let Some(delay) = script.list_changed_after_ms {
+        let tools = tools.clone();
+        let replacement = script.tools_after_change.clone();
+        let output = output.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay));
+            *tools.lock().unwrap() = replacement;
+            output.send(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/tools/list_changed",
+            }));
+        });
+    }
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            eprintln!("WARN ignoring unreadable message: {}", line);
+            continue;
+        };
+        let Some(id) = message.get("id").cloned() else {
+            // Notifications need no answer
+            continue;
+        };
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+
+        match method {
+            "initialize" => {
+                thread::sleep(Duration::from_millis(script.initialize_delay_ms));
+                let mut capabilities = json!({
+                    "tools": { "listChanged": true },
+                    "logging": {},
+                });
+                // Only advertise what the script offers, like a real server would
+                if !script.resources.is_empty() {
+                    capabilities["resources"] = json!({});
+                }
+                if !script.prompts.is_empty() {
+                    capabilities["prompts"] = json!({});
+                }
+                output.send(result(
+                    id,
+                    json!({
+                        "protocolVersion": message
+                            .pointer("/params/protocolVersion")
+                            .cloned()
+                            .unwrap_or(json!("2025-03-26")),
+                        "capabilities": capabilities,
+                        "serverInfo": { "name": "mock-mcp-server", "version": "0.1.0" },
+                    }),
+                ));
+            }
+            "tools/list" => {
+                let definitions: Vec<Value> =
+                    tools.lock().unwrap().iter().map(MockTool::definition).collect();
+                output.send(result(id, json!({ "tools": definitions })));
+            }
+            "resources/list" => output.send(result(id, json!({ "resources": script.resources }))),
+            "prompts/list" => output.send(result(id, json!({ "prompts": script.prompts }))),
+            "tools/call" => {
+                let name = message
+                    .pointer("/params/name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let tool = tools.lock().unwrap().iter().find(|t| t.name == name).cloned();
+                match tool {
+                    Some(tool) => {
+                        let output = output.clone();
+                        let exit_code = script.exit_code;
+                        thread::spawn(move || call_tool(&tool, id, &message, &output, exit_code));
+                    }
+                    None => output.send(error(id, -32602, &format!("Unknown tool: {}", name))),
+                }
+            }
+            "logging/setLevel" | "ping" => output.send(result(id, json!({}))),
+            method => output.send(error(id, -32601, &format!("Method not found: {}", method))),
+        }
+    }
+
+    eprintln!("INFO input closed");
+    if script.ignore_input_close {
+        loop {
+            thread::park();
+        }
+    }
+}
+
+/// Answer a tool call according to the tool's script
+fn call_tool(tool: &MockTool, id: Value, request: &Value, output: &Output, exit_code: i32) {
+    for line in &tool.stderr {
+        eprintln!("{}", line);
+    }
+    if tool.crash {
+        std::process::exit(exit_code);
+    }
+
+    let token = request.pointer("/params/_meta/progressToken").cloned();
+    let steps = tool.progress.max(1);
+    for step in 1..=tool.progress {
+        if let Some(token) = &token {
+            output.send(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": {
+                    "progressToken": token,
+                    "progress": step,
+                    "total": tool.progress,
+                    "message": format!("step {} of {}", step, tool.progress),
+                },
+            }));
+        }
+        thread::sleep(Duration::from_millis(tool.delay_ms / u64::from(steps)));
+    }
+    if tool.progress == 0 {
+        thread::sleep(Duration::from_millis(tool.delay_ms));
+    }
+    let busy_until = Instant::now() + Duration::from_millis(tool.busy_ms);
+    while Instant::now() < busy_until {
+        std::hint::spin_loop();
+    }
+    if tool.allocate_mb > 0 {
+        // Filled so the pages are resident
+        vec![1u8; tool.allocate_mb * 1024 * 1024].leak();
+    }
+    if let Some((command, args)) = tool.spawn.split_first() {
+        if let Err(e) = std::process::Command::new(command).args(args).spawn() {
+            output.send(error(id, -32603, &format!("Failed to spawn '{}': {}", command, e)));
+            return;
+        }
+    }
+
+    if let Some(message) = &tool.error {
+        output.send(error(id, -32603, message));
+        return;
+    }
+    let text = tool.env.as_ref().map(|name| {
+        let value = std::env::var(name).unwrap_or_default();
+        eprintln!("DEBUG {}={}", name, value);
+        value
+    });
+    let mut is_error = tool.is_error;
+    let text = text.or_else(|| {
+        tool.read_file.as_ref().map(|path| {
+            std::fs::read_to_string(path).unwrap_or_else(|e| {
+                is_error = true;
+                e.to_string()
+            })
+        })
+    });
+    let text = text.or_else(|| {
+        tool.connect.as_ref().map(|address| {
+            let connected = address
+                .parse()
+                .map_err(|e: std::net::AddrParseError| e.to_string())
+                .and_then(|address| {
+                    TcpStream::connect_timeout(&address, Duration::from_secs(2))
+                        .map_err(|e| e.to_string())
+                });
+            match connected {
+                Ok(_) => "connected".to_string(),
+                Err(e) => {
+                    is_error = true;
+                    e
+                }
+            }
+        })
+    });
+    let answer = match (&tool.result, text.or_else(|| tool.text.clone())) {
+        (Some(answer), _) => answer.clone(),
+        (None, text) => {
+            let text = text.unwrap_or_else(|| {
+                request
+                    .pointer("/params/arguments")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}))
+                    .to_string()
+            });
+            json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            })
+        }
+    };
+    output.send(result(id, answer));
+}
+
+/// Read the script from the command line
+fn read_script() -> Result<Script, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let source = match args.as_slice() {
+        [] => return Ok(Script::default()),
+        [flag, script] if flag == "--script" => script.clone(),
+        [flag, path] if flag == "--script-file" => std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read script '{}': {}", path, e))?,
+        _ => return Err("usage: mock-mcp-server [--script <json> | --script-file <path>]".into()),
+    };
+    serde_json::from_str(&source).map_err(|e| format!("invalid script: {}", e))
+}
+
+fn result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
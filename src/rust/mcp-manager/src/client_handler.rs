@@ -0,0 +1,99 @@
+use crate::events::EventEmitter;
+use crate::models::McpEvent;
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam, ProgressNotificationParam, Tool};
+use rmcp::service::{NotificationContext, RoleClient};
+use rmcp::ClientHandler;
+
+/// Receives the tools of a server after it reported that they changed
+pub type ToolsChangedHook = Box<dyn Fn(Vec<Tool>) + Send + Sync>;
+
+/// Handles requests and notifications a server sends to the manager
+pub struct McpClientHandler {
+    /// ID of the server this handler belongs to
+    server_id: String,
+    /// Destination for events
+    events: EventEmitter,
+    /// Stores the tools fetched after the server's tool list changed
+    tools_changed: ToolsChangedHook,
+}
+
+impl McpClientHandler {
+    /// Create a handler for a server
+    pub fn new(server_id: String, events: EventEmitter, tools_changed: ToolsChangedHook) -> Self {
+        Self {
+            server_id,
+            events,
+            tools_changed,
+        }
+    }
+}
+
+impl ClientHandler for McpClientHandler {
+    /// Forward `notifications/message` as log events
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.events.emit(McpEvent::server_log(
+            log_level_name(params.level),
+            params.logger.as_deref(),
+            &params.data,
+            &self.server_id,
+        ));
+    }
+
+    /// Forward `notifications/progress` as progress events
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.events.emit(McpEvent::progress(
+            &self.server_id,
+            &params.progress_token.0.to_string(),
+            params.progress,
+            params.total,
+            params.message.as_deref(),
+        ));
+    }
+
+    /// Fetch the tools again on `notifications/tools/list_changed`
+    async fn on_tool_list_changed(&self, context: NotificationContext<RoleClient>) {
+        match context.peer.list_all_tools().await {
+            Ok(tools) => (self.tools_changed)(tools),
+            Err(e) => self.events.emit(McpEvent::warn(
+                &format!("Failed to list changed tools: {}", e),
+                Some(&self.server_id),
+            )),
+        }
+    }
+}
+
+/// Map MCP logging levels onto the levels used in log events
+pub fn log_level_name(level: LoggingLevel) -> &'static str {
+    match level {
+        LoggingLevel::Debug => "debug",
+        LoggingLevel::Info | LoggingLevel::Notice => "info",
+        LoggingLevel::Warning => "warn",
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => "error",
+    }
+}
+
+/// Parse an MCP logging level, also accepting the level names used in log events
+pub fn parse_log_level(level: &str) -> Option<LoggingLevel> {
+    match level.to_ascii_lowercase().as_str() {
+        "debug" | "trace" => Some(LoggingLevel::Debug),
+        "info" => Some(LoggingLevel::Info),
+        "notice" => Some(LoggingLevel::Notice),
+        "warn" | "warning" => Some(LoggingLevel::Warning),
+        "error" => Some(LoggingLevel::Error),
+        "critical" => Some(LoggingLevel::Critical),
+        "alert" => Some(LoggingLevel::Alert),
+        "emergency" => Some(LoggingLevel::Emergency),
+        _ => None,
+    }
+}
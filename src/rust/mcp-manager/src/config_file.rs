@@ -0,0 +1,178 @@
+use crate::error::{Error, Result};
+use crate::models::ServerConfig;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Transports that reach the server over the network rather than stdio
+const REMOTE_TRANSPORTS: &[&str] = &["sse", "http", "streamableHttp", "streamable-http"];
+
+/// Servers defined by a config file
+#[derive(Debug, Clone, Default)]
+pub struct ServerDefinitions {
+    /// Servers to run, in file order
+    pub servers: Vec<ServerConfig>,
+    /// IDs of defined servers that will not run, with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Top level of a `claude_desktop_config.json` or VS Code `mcp.json` file
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigFile {
+    #[serde(default, deserialize_with = "ordered_map")]
+    mcp_servers: Option<Vec<(String, ServerEntry)>>,
+    #[serde(default, deserialize_with = "ordered_map")]
+    servers: Option<Vec<(String, ServerEntry)>>,
+}
+
+/// One server of a config file; unknown fields are ignored
+#[derive(Deserialize)]
+struct ServerEntry {
+    /// Transport, as used by VS Code (`stdio`, `sse` or `http`)
+    #[serde(rename = "type")]
+    transport: Option<String>,
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    env: Option<HashMap<String, String>>,
+    url: Option<String>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+/// Read server definitions from a config file path or from inline JSON
+pub fn load_server_definitions(source: &str) -> Result<ServerDefinitions> {
+    if source.trim_start().starts_with('{') {
+        return parse_server_definitions(source, "inline config");
+    }
+    let content = std::fs::read_to_string(source).map_err(|e| {
+        Error::ConfigError(format!("Failed to read config '{}': {}", source, e))
+    })?;
+    parse_server_definitions(&content, &format!("config '{}'", source))
+}
+
+/// Parse the `mcpServers` (or VS Code `servers`) section of a config file
+///
+/// Errors name the offending field as a path such as `mcpServers.github.args[1]`,
+/// with the line and column where JSON parsing failed.
+pub fn parse_server_definitions(content: &str, origin: &str) -> Result<ServerDefinitions> {
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    let file: ConfigFile =
+        serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+            let path = e.path().to_string();
+            match path.as_str() {
+                "." => Error::ConfigError(format!("Invalid {}: {}", origin, e.into_inner())),
+                _ => Error::ConfigError(format!("Invalid {} at {}: {}", origin, path, e.into_inner())),
+            }
+        })?;
+    deserializer
+        .end()
+        .map_err(|e| Error::ConfigError(format!("Invalid {}: {}", origin, e)))?;
+
+    let (section, entries) = match (file.mcp_servers, file.servers) {
+        (Some(entries), None) => ("mcpServers", entries),
+        (None, Some(entries)) => ("servers", entries),
+        (Some(_), Some(_)) => {
+            return Err(Error::ConfigError(format!(
+                "Invalid {}: both `mcpServers` and `servers` are defined",
+                origin
+            )))
+        }
+        (None, None) => {
+            return Err(Error::ConfigError(format!(
+                "Invalid {}: no `mcpServers` or `servers` section",
+                origin
+            )))
+        }
+    };
+
+    let invalid = |location: String, message: &str| {
+        Error::ConfigError(format!("Invalid {} at {}: {}", origin, location, message))
+    };
+
+    let mut definitions = ServerDefinitions::default();
+    let mut seen = HashSet::new();
+    for (id, entry) in entries {
+        let location = format!("{}.{}", section, id);
+        if id.trim().is_empty() {
+            return Err(invalid(section.to_string(), "server IDs must not be empty"));
+        }
+        if !seen.insert(id.clone()) {
+            return Err(invalid(location, "server is defined more than once"));
+        }
+
+        let remote = match entry.transport.as_deref() {
+            None => entry.url.is_some(),
+            Some("stdio") => false,
+            Some(transport) if REMOTE_TRANSPORTS.contains(&transport) => true,
+            Some(transport) => {
+                return Err(invalid(
+                    format!("{}.type", location),
+                    &format!("unknown transport '{}'", transport),
+                ))
+            }
+        };
+
+        if remote {
+            if entry.url.as_deref().is_none_or(str::is_empty) {
+                return Err(invalid(format!("{}.url", location), "required for remote servers"));
+            }
+            if entry.command.is_some() {
+                return Err(invalid(location, "`command` and `url` cannot both be set"));
+            }
+            let reason = match entry.disabled {
+                true => "disabled",
+                false => "remote servers are not supported",
+            };
+            definitions.skipped.push((id, reason.to_string()));
+            continue;
+        }
+
+        let Some(command) = entry.command.filter(|command| !command.trim().is_empty()) else {
+            return Err(invalid(format!("{}.command", location), "required for stdio servers"));
+        };
+        if entry.disabled {
+            definitions.skipped.push((id, "disabled".to_string()));
+            continue;
+        }
+
+        definitions.servers.push(ServerConfig {
+            id,
+            command,
+            args: entry.args,
+            env: entry.env,
+            ..Default::default()
+        });
+    }
+    Ok(definitions)
+}
+
+/// Deserialize a JSON object into its entries in file order
+fn ordered_map<'de, D, V>(deserializer: D) -> std::result::Result<Option<Vec<(String, V)>>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    struct OrderedMap<V>(PhantomData<V>);
+
+    impl<'de, V: Deserialize<'de>> Visitor<'de> for OrderedMap<V> {
+        type Value = Vec<(String, V)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an object of server definitions")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                entries.push((key, map.next_value()?));
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_map(OrderedMap(PhantomData)).map(Some)
+}
@@ -0,0 +1,77 @@
+use crate::error::{Error, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How long a config file must stay unchanged before it is reloaded, when not configured
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Reports debounced changes of a config file
+///
+/// The directory holding the file is watched rather than the file itself, so
+/// editors that save by writing a new file and renaming it over the old one
+/// are noticed too. Watching stops when this is dropped.
+pub struct ConfigWatcher {
+    /// Keeps the inotify (or platform equivalent) watch alive
+    _watcher: RecommendedWatcher,
+    /// Raw change notifications for the file
+    changes: mpsc::UnboundedReceiver<()>,
+    /// Quiet period that ends a burst of changes
+    debounce: Duration,
+}
+
+impl ConfigWatcher {
+    /// Start watching a config file
+    pub fn new(path: &Path, debounce: Duration) -> Result<Self> {
+        let path = std::path::absolute(path).map_err(|e| watch_error(path, e))?;
+        let directory = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("/"));
+
+        let (tx, changes) = mpsc::unbounded_channel();
+        let file = path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            if event.paths.iter().any(|changed| changed == &file) {
+                let _ = tx.send(());
+            }
+        })
+        .map_err(|e| watch_error(&path, e))?;
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(&path, e))?;
+
+        Ok(Self {
+            _watcher: watcher,
+            changes,
+            debounce,
+        })
+    }
+
+    /// Wait for the file to change and then stay unchanged for the debounce period
+    ///
+    /// Returns `false` once the watch has ended.
+    pub async fn changed(&mut self) -> bool {
+        if self.changes.recv().await.is_none() {
+            return false;
+        }
+        loop {
+            match tokio::time::timeout(self.debounce, self.changes.recv()).await {
+                Ok(Some(())) => continue,
+                Ok(None) => return false,
+                Err(_) => return true,
+            }
+        }
+    }
+}
+
+fn watch_error(path: &Path, error: impl std::fmt::Display) -> Error {
+    Error::ConfigError(format!("Failed to watch config '{}': {}", path.display(), error))
+}
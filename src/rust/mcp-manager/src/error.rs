@@ -1,10 +1,13 @@
-use napi::Error as NapiError;
-use napi_derive::napi;
-use rmcp::Error as McpError;
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{JsError, ToNapiValue};
+#[cfg(feature = "napi")]
+use napi::{sys, JsUnknown, NapiValue};
+use rmcp::ErrorData as McpError;
 use thiserror::Error;
 
 /// Custom error types for the MCP server management library
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     /// Error from the MCP protocol library
     #[error("MCP protocol error: {0}")]
@@ -34,6 +37,14 @@ pub enum Error {
     #[error("Tool execution error: {0}")]
     ToolExecutionError(String),
 
+    /// Error when a tool call was not approved
+    #[error("Tool call denied: {0}")]
+    ApprovalDenied(String),
+
+    /// Error in a server configuration
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
     /// Generic error
     #[error("{0}")]
     Other(String),
@@ -42,49 +53,95 @@ pub enum Error {
 /// Result type alias for the MCP server management library
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Stable error code exposed to TypeScript
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::ServerNotFound(_) => error_codes::SERVER_NOT_FOUND,
+            Error::ToolNotFound(_) => error_codes::TOOL_NOT_FOUND,
+            Error::ProcessStartError(_) => error_codes::PROCESS_START_ERROR,
+            Error::ProcessStopError(_) => error_codes::PROCESS_STOP_ERROR,
+            Error::CommunicationError(_) => error_codes::COMMUNICATION_ERROR,
+            Error::ToolExecutionError(_) => error_codes::TOOL_EXECUTION_ERROR,
+            Error::McpError(_) => error_codes::MCP_ERROR,
+            Error::ApprovalDenied(_) => error_codes::APPROVAL_DENIED,
+            Error::ConfigError(_) => error_codes::CONFIG_ERROR,
+            Error::Other(_) => error_codes::UNKNOWN_ERROR,
+        }
+    }
+}
+
+/// NAPI error whose status is the error code, which JavaScript sees as `err.code`
+#[cfg(feature = "napi")]
+pub type NapiError = napi::Error<&'static str>;
+
 /// Convert internal errors to NAPI errors for TypeScript
+#[cfg(feature = "napi")]
 impl From<Error> for NapiError {
     fn from(err: Error) -> Self {
-        let error_message = err.to_string();
-        let error_code = match err {
-            Error::ServerNotFound(_) => "SERVER_NOT_FOUND",
-            Error::ToolNotFound(_) => "TOOL_NOT_FOUND",
-            Error::ProcessStartError(_) => "PROCESS_START_ERROR",
-            Error::ProcessStopError(_) => "PROCESS_STOP_ERROR",
-            Error::CommunicationError(_) => "COMMUNICATION_ERROR",
-            Error::ToolExecutionError(_) => "TOOL_EXECUTION_ERROR",
-            Error::McpError(_) => "MCP_ERROR",
-            Error::Other(_) => "UNKNOWN_ERROR",
-        };
-
-        NapiError::new(napi::Status::GenericFailure, error_message)
+        NapiError::new(err.code(), err.to_string())
+    }
+}
+
+/// Outcome of an async binding, whose error rejects the promise with its error code
+///
+/// NAPI-RS rejects promises with its own statuses only, so the JavaScript error
+/// is made here, once the outcome is back on the JavaScript thread.
+#[cfg(feature = "napi")]
+pub struct NapiResult<T>(Result<T>);
+
+#[cfg(feature = "napi")]
+impl<T> From<Result<T>> for NapiResult<T> {
+    fn from(result: Result<T>) -> Self {
+        Self(result)
+    }
+}
+
+#[cfg(feature = "napi")]
+impl<T: ToNapiValue> ToNapiValue for NapiResult<T> {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        match val.0 {
+            Ok(value) => T::to_napi_value(env, value),
+            Err(err) => {
+                let error = JsError::from(NapiError::from(err)).into_value(env);
+                Err(JsUnknown::from_raw_unchecked(env, error).into())
+            }
+        }
     }
 }
 
 /// JavaScript-exposed error codes
-#[napi]
 pub mod error_codes {
-    #[napi(js_name = "SERVER_NOT_FOUND")]
+    #[cfg(feature = "napi")]
+    use napi_derive::napi;
+
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const SERVER_NOT_FOUND: &str = "SERVER_NOT_FOUND";
 
-    #[napi(js_name = "TOOL_NOT_FOUND")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const TOOL_NOT_FOUND: &str = "TOOL_NOT_FOUND";
 
-    #[napi(js_name = "PROCESS_START_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const PROCESS_START_ERROR: &str = "PROCESS_START_ERROR";
 
-    #[napi(js_name = "PROCESS_STOP_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const PROCESS_STOP_ERROR: &str = "PROCESS_STOP_ERROR";
 
-    #[napi(js_name = "COMMUNICATION_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const COMMUNICATION_ERROR: &str = "COMMUNICATION_ERROR";
 
-    #[napi(js_name = "TOOL_EXECUTION_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const TOOL_EXECUTION_ERROR: &str = "TOOL_EXECUTION_ERROR";
 
-    #[napi(js_name = "MCP_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const MCP_ERROR: &str = "MCP_ERROR";
 
-    #[napi(js_name = "UNKNOWN_ERROR")]
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
+    pub const APPROVAL_DENIED: &str = "APPROVAL_DENIED";
+
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
+    pub const CONFIG_ERROR: &str = "CONFIG_ERROR";
+
+    #[cfg_attr(feature = "napi", napi(namespace = "error_codes"))]
     pub const UNKNOWN_ERROR: &str = "UNKNOWN_ERROR";
 }
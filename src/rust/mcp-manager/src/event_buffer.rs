@@ -0,0 +1,141 @@
+use crate::log_parser::level_rank;
+use crate::models::{EventFilter, EventQuery, McpEvent};
+use std::collections::{HashMap, VecDeque};
+
+/// Number of recent events kept across all servers when not configured
+pub const DEFAULT_EVENT_BUFFER_SIZE: usize = 1000;
+
+/// Number of recent events kept for each server when not configured
+pub const DEFAULT_SERVER_EVENT_BUFFER_SIZE: usize = 200;
+
+/// Bounded history of recently emitted events
+///
+/// Events are kept in a global ring and in a ring per server, so a chatty
+/// server cannot push the history of quieter servers out of reach.
+pub struct EventBuffer {
+    /// Recent events across all servers
+    global: VecDeque<McpEvent>,
+    /// Recent events by server ID
+    servers: HashMap<String, VecDeque<McpEvent>>,
+    /// Capacity of the global ring
+    capacity: usize,
+    /// Capacity of each server's ring
+    server_capacity: usize,
+    /// Sequence number of the next event
+    next_sequence: i64,
+}
+
+impl EventBuffer {
+    /// Create an empty buffer
+    pub fn new(capacity: usize, server_capacity: usize) -> Self {
+        Self {
+            global: VecDeque::new(),
+            servers: HashMap::new(),
+            capacity,
+            server_capacity,
+            next_sequence: 1,
+        }
+    }
+
+    /// Take the sequence number for a new event
+    pub fn next_sequence(&mut self) -> i64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+
+    /// Record an event, evicting the oldest ones when full
+    pub fn push(&mut self, event: &McpEvent) {
+        push_bounded(&mut self.global, event.clone(), self.capacity);
+        if let Some(server_id) = event.server_id() {
+            let ring = self.servers.entry(server_id.to_string()).or_default();
+            push_bounded(ring, event.clone(), self.server_capacity);
+        }
+    }
+
+    /// Get the buffered events matching a query, oldest first
+    pub fn query(&self, query: &EventQuery) -> Vec<McpEvent> {
+        let ring = match &query.server_id {
+            Some(server_id) => match self.servers.get(server_id) {
+                Some(ring) => ring,
+                None => return Vec::new(),
+            },
+            None => &self.global,
+        };
+
+        let mut events: Vec<McpEvent> = ring
+            .iter()
+            .filter(|event| matches(event, query))
+            .cloned()
+            .collect();
+
+        if let Some(limit) = query.limit {
+            let excess = events.len().saturating_sub(limit as usize);
+            events.drain(..excess);
+        }
+        events
+    }
+}
+
+/// Check whether an event passes a query's filters
+pub fn matches(event: &McpEvent, query: &EventQuery) -> bool {
+    if query
+        .server_id
+        .as_ref()
+        .is_some_and(|server_id| event.server_id() != Some(server_id.as_str()))
+    {
+        return false;
+    }
+    if query
+        .since_sequence
+        .is_some_and(|since| event.sequence() <= since)
+    {
+        return false;
+    }
+    if query
+        .event_types
+        .as_ref()
+        .is_some_and(|types| !types.iter().any(|t| t == event.event_type()))
+    {
+        return false;
+    }
+    meets_min_level(event, query.min_level.as_deref())
+}
+
+/// Check whether an event passes a subscriber's filter
+pub fn matches_filter(event: &McpEvent, filter: &EventFilter) -> bool {
+    if filter
+        .event_types
+        .as_ref()
+        .is_some_and(|types| !types.iter().any(|t| t == event.event_type()))
+    {
+        return false;
+    }
+    if filter.server_ids.as_ref().is_some_and(|server_ids| {
+        event
+            .server_id()
+            .is_none_or(|server_id| !server_ids.iter().any(|id| id == server_id))
+    }) {
+        return false;
+    }
+    meets_min_level(event, filter.min_level.as_deref())
+}
+
+/// Check a log event against a minimum level; other events always pass
+fn meets_min_level(event: &McpEvent, min_level: Option<&str>) -> bool {
+    match (min_level, event) {
+        (Some(min_level), McpEvent::Log(log)) => level_rank(&log.level) >= level_rank(min_level),
+        _ => true,
+    }
+}
+
+/// Append to a ring, dropping the oldest entries beyond its capacity
+fn push_bounded(ring: &mut VecDeque<McpEvent>, event: McpEvent, capacity: usize) {
+    if capacity == 0 {
+        return;
+    }
+    while ring.len() >= capacity {
+        ring.pop_front();
+    }
+    ring.push_back(event);
+}
@@ -0,0 +1,277 @@
+use crate::event_buffer::{self, EventBuffer};
+use crate::log_files::{LogFiles, format_timestamp};
+use crate::models::{Direction, EventFilter, EventQuery, McpEvent, now_millis};
+use crate::redaction::Redactor;
+use crate::trace::Tracer;
+#[cfg(feature = "napi")]
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use serde_json::Value;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Receives the events emitted by the manager
+///
+/// Events are delivered while the manager holds its subscribers lock, so a sink
+/// must not call back into the manager: subscribing, unsubscribing or anything
+/// that emits an event from [`EventSink::send`] deadlocks. Hand events over to
+/// another task, e.g. through a channel, to act on them.
+pub trait EventSink: Send + Sync {
+    /// Deliver an event; called while the manager holds locks, so it must not block
+    fn send(&self, event: McpEvent);
+}
+
+/// Any thread-safe closure taking events is a sink
+impl<F> EventSink for F
+where
+    F: Fn(McpEvent) + Send + Sync,
+{
+    fn send(&self, event: McpEvent) {
+        self(event);
+    }
+}
+
+#[cfg(feature = "napi")]
+impl EventSink for ThreadsafeFunction<McpEvent> {
+    fn send(&self, event: McpEvent) {
+        self.call(Ok(event), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+}
+
+/// A callback registered with `subscribe` and the events it wants
+struct Subscription {
+    id: u32,
+    filter: EventFilter,
+    callback: Arc<dyn EventSink>,
+}
+
+/// Everyone events are delivered to
+struct Subscribers {
+    /// Callback given when the manager was created; receives every event
+    main: Arc<dyn EventSink>,
+    /// Filtered subscriptions in registration order
+    subscriptions: Vec<Subscription>,
+    /// ID of the next subscription
+    next_id: u32,
+}
+
+/// Delivers events to TypeScript and to the manager's persistent sinks
+#[derive(Clone)]
+pub struct EventEmitter {
+    /// Callbacks for events
+    subscribers: Arc<RwLock<Subscribers>>,
+    /// Recently emitted events, kept for late subscribers; also numbers the events
+    buffer: Arc<Mutex<EventBuffer>>,
+    /// Captured traffic of traced servers, where trace events are kept
+    tracer: Arc<Tracer>,
+    /// Per-server log files, when a log directory is configured
+    log_files: Option<Arc<LogFiles>>,
+    /// Whether JSON-RPC traffic is written to the log files
+    log_traffic: bool,
+    /// Masks secrets in every event and in logged traffic
+    redactor: Arc<Redactor>,
+}
+
+impl EventEmitter {
+    /// Create an emitter
+    pub fn new(
+        callback: Arc<dyn EventSink>,
+        buffer: EventBuffer,
+        tracer: Arc<Tracer>,
+        log_files: Option<LogFiles>,
+        log_traffic: bool,
+        redactor: Redactor,
+    ) -> Self {
+        Self {
+            subscribers: Arc::new(RwLock::new(Subscribers {
+                main: callback,
+                subscriptions: Vec::new(),
+                next_id: 1,
+            })),
+            buffer: Arc::new(Mutex::new(buffer)),
+            tracer,
+            log_files: log_files.map(Arc::new),
+            log_traffic,
+            redactor: Arc::new(redactor),
+        }
+    }
+
+    /// Emit an event to TypeScript, recording it in the buffer and the server's log file
+    pub fn emit(&self, mut event: McpEvent) {
+        redact_event(&self.redactor, &mut event);
+        // Holding the subscribers lock keeps a new subscriber's replay from missing this event
+        let subscribers = self.subscribers.read().unwrap();
+        // Holding the buffer lock until delivered keeps sequence, buffer and delivery order the same
+        let mut buffer = self.buffer.lock().unwrap();
+        event.set_sequence(buffer.next_sequence());
+        match &event {
+            // Traced messages have a buffer of their own and are logged as traffic if at all
+            McpEvent::Trace(entry) => self.tracer.store(entry),
+            event => {
+                buffer.push(event);
+                if let (Some(log_files), Some(server_id)) = (&self.log_files, event.server_id()) {
+                    log_files.write(server_id, &describe(event));
+                }
+            }
+        }
+        for subscription in &subscribers.subscriptions {
+            if event_buffer::matches_filter(&event, &subscription.filter) {
+                subscription.callback.send(event.clone());
+            }
+        }
+        subscribers.main.send(event);
+    }
+
+    /// Replace the event callback, first replaying buffered events newer than a sequence number
+    ///
+    /// No event is emitted while the buffered ones are replayed, so the new
+    /// callback sees every event exactly once and in order.
+    pub fn set_callback(&self, callback: Arc<dyn EventSink>, replay_since: Option<i64>) {
+        let mut subscribers = self.subscribers.write().unwrap();
+        self.replay(callback.as_ref(), &EventFilter::default(), replay_since);
+        subscribers.main = callback;
+    }
+
+    /// Register a filtered callback and return its subscription ID
+    ///
+    /// Buffered events newer than `replay_since` that pass the filter are
+    /// delivered before any new event.
+    pub fn subscribe(
+        &self,
+        filter: EventFilter,
+        callback: Arc<dyn EventSink>,
+        replay_since: Option<i64>,
+    ) -> u32 {
+        let mut subscribers = self.subscribers.write().unwrap();
+        self.replay(callback.as_ref(), &filter, replay_since);
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.subscriptions.push(Subscription {
+            id,
+            filter,
+            callback,
+        });
+        id
+    }
+
+    /// Remove a subscription, returning whether it was registered
+    pub fn unsubscribe(&self, id: u32) -> bool {
+        let mut subscribers = self.subscribers.write().unwrap();
+        let before = subscribers.subscriptions.len();
+        subscribers
+            .subscriptions
+            .retain(|subscription| subscription.id != id);
+        subscribers.subscriptions.len() != before
+    }
+
+    /// Deliver buffered events newer than a sequence number that pass a filter
+    fn replay(
+        &self,
+        callback: &dyn EventSink,
+        filter: &EventFilter,
+        replay_since: Option<i64>,
+    ) {
+        let Some(since) = replay_since else {
+            return;
+        };
+        let query = EventQuery {
+            since_sequence: Some(since),
+            ..Default::default()
+        };
+        for event in self.buffer.lock().unwrap().query(&query) {
+            if event_buffer::matches_filter(&event, filter) {
+                callback.send(event);
+            }
+        }
+    }
+
+    /// Get the buffered events matching a query, oldest first
+    pub fn recent(&self, query: &EventQuery) -> Vec<McpEvent> {
+        self.buffer.lock().unwrap().query(query)
+    }
+
+    /// Whether JSON-RPC traffic needs to be observed for the log files
+    pub fn logs_traffic(&self) -> bool {
+        self.log_traffic && self.log_files.is_some()
+    }
+
+    /// Record a JSON-RPC message exchanged with a server
+    pub fn record_traffic(&self, server_id: &str, direction: Direction, message: &Value) {
+        if let (true, Some(log_files)) = (self.log_traffic, &self.log_files) {
+            log_files.write(
+                server_id,
+                &format!(
+                    "{} RPC   {} {}",
+                    format_timestamp(now_millis()),
+                    direction.arrow(),
+                    message
+                ),
+            );
+        }
+    }
+
+    /// Masks secrets in events and traffic
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Per-server log files, when a log directory is configured
+    pub fn log_files(&self) -> Option<&LogFiles> {
+        self.log_files.as_deref()
+    }
+}
+
+/// Mask secrets in the text an event carries
+fn redact_event(redactor: &Redactor, event: &mut McpEvent) {
+    match event {
+        McpEvent::Log(log) => {
+            redactor.redact_field(&mut log.message);
+            if let Some(data) = &mut log.data {
+                redactor.redact_field(data);
+            }
+        }
+        McpEvent::ServerCrashed(crashed) => redactor.redact_field(&mut crashed.reason),
+        McpEvent::Progress(progress) => {
+            if let Some(message) = &mut progress.message {
+                redactor.redact_field(message);
+            }
+        }
+        McpEvent::ConfigReloaded(reloaded) => {
+            for error in reloaded.changes.iter_mut().filter_map(|change| change.error.as_mut()) {
+                redactor.redact_field(error);
+            }
+            if let Some(error) = &mut reloaded.error {
+                redactor.redact_field(error);
+            }
+        }
+        // Traced messages are redacted when they are captured
+        McpEvent::Trace(_) => {}
+        McpEvent::ServerStarted(_)
+        | McpEvent::ServerStopped(_)
+        | McpEvent::ToolsUpdated(_)
+        | McpEvent::ServerStats(_)
+        | McpEvent::ResourceLimit(_) => {}
+    }
+}
+
+/// Render an event as a log file line
+fn describe(event: &McpEvent) -> String {
+    if let McpEvent::Log(log) = event {
+        let logger = log
+            .logger
+            .as_ref()
+            .map(|logger| format!("[{}] ", logger))
+            .unwrap_or_default();
+        return format!(
+            "{} {:<5} {}{}",
+            format_timestamp(log.timestamp),
+            log.level.to_uppercase(),
+            logger,
+            log.message
+        );
+    }
+    format!(
+        "{} EVENT {} {}",
+        format_timestamp(now_millis()),
+        event.event_type(),
+        serde_json::to_string(event).unwrap_or_default()
+    )
+}
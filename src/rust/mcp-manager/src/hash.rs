@@ -0,0 +1,13 @@
+/// 32-bit FNV-1a hash, used where a short hash must be stable across builds and platforms
+pub fn fnv1a(input: &[u8]) -> u32 {
+    input.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// 64-bit FNV-1a hash, used where a hash must be stable across builds and platforms
+pub fn fnv1a_64(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -0,0 +1,177 @@
+use crate::error::{Error, Result};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serde_json::{Map, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// A tool call as seen by interceptors
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    /// ID of the server the tool runs on
+    pub server_id: String,
+    /// Exposed name of the tool
+    pub tool_name: String,
+    /// Arguments the tool will be called with
+    pub arguments: Map<String, Value>,
+}
+
+/// Hook around tool calls
+///
+/// Interceptors run in registration order before the call and in reverse order
+/// after it, so the first interceptor registered sees the final result. Returning
+/// an error from either hook fails the call.
+pub trait ToolInterceptor: Send + Sync {
+    /// Short name used in error messages
+    fn name(&self) -> &str;
+
+    /// Inspect or rewrite a call before it is sent to the server
+    fn before_call(&self, call: ToolCall) -> BoxFuture<'static, Result<ToolCall>> {
+        futures::future::ready(Ok(call)).boxed()
+    }
+
+    /// Inspect or rewrite the result of a call (a serialized `CallToolResult`)
+    fn after_call(&self, _call: &ToolCall, result: Value) -> BoxFuture<'static, Result<Value>> {
+        futures::future::ready(Ok(result)).boxed()
+    }
+}
+
+/// Ordered list of interceptors applied to every tool call
+pub struct InterceptorChain {
+    /// Registered interceptors with their IDs, in registration order
+    interceptors: RwLock<Vec<(u32, Arc<dyn ToolInterceptor>)>>,
+    /// ID assigned to the next registered interceptor
+    next_id: AtomicU32,
+}
+
+impl InterceptorChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self {
+            interceptors: RwLock::new(Vec::new()),
+            next_id: AtomicU32::new(1),
+        }
+    }
+
+    /// Append an interceptor to the chain and return its ID
+    pub fn add(&self, interceptor: Arc<dyn ToolInterceptor>) -> u32 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.interceptors.write().unwrap().push((id, interceptor));
+        id
+    }
+
+    /// Remove an interceptor, returning whether it was registered
+    pub fn remove(&self, id: u32) -> bool {
+        let mut interceptors = self.interceptors.write().unwrap();
+        let before = interceptors.len();
+        interceptors.retain(|(interceptor_id, _)| *interceptor_id != id);
+        interceptors.len() != before
+    }
+
+    /// Snapshot of the interceptors, so none is held locked while awaiting hooks
+    fn snapshot(&self) -> Vec<Arc<dyn ToolInterceptor>> {
+        self.interceptors
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, interceptor)| interceptor.clone())
+            .collect()
+    }
+
+    /// Run every before-call hook in order
+    pub async fn before_call(&self, mut call: ToolCall) -> Result<ToolCall> {
+        for interceptor in self.snapshot() {
+            call = interceptor
+                .before_call(call)
+                .await
+                .map_err(|e| interceptor_error(interceptor.as_ref(), e))?;
+        }
+        Ok(call)
+    }
+
+    /// Run every after-call hook in reverse order
+    pub async fn after_call(&self, call: &ToolCall, mut result: Value) -> Result<Value> {
+        for interceptor in self.snapshot().into_iter().rev() {
+            result = interceptor
+                .after_call(call, result)
+                .await
+                .map_err(|e| interceptor_error(interceptor.as_ref(), e))?;
+        }
+        Ok(result)
+    }
+}
+
+/// Attribute a hook failure to the interceptor that caused it
+fn interceptor_error(interceptor: &dyn ToolInterceptor, error: Error) -> Error {
+    Error::ToolExecutionError(format!(
+        "Interceptor '{}' failed: {}",
+        interceptor.name(),
+        error
+    ))
+}
+
+impl Default for InterceptorChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fills in arguments the caller left out
+pub struct ArgumentDefaults {
+    /// Only apply to tools of this server
+    pub server_id: Option<String>,
+    /// Only apply to tools with this name
+    pub tool_name: Option<String>,
+    /// Values for arguments that are missing from the call
+    pub defaults: Map<String, Value>,
+}
+
+impl ToolInterceptor for ArgumentDefaults {
+    fn name(&self) -> &str {
+        "argumentDefaults"
+    }
+
+    fn before_call(&self, mut call: ToolCall) -> BoxFuture<'static, Result<ToolCall>> {
+        let applies = self.server_id.as_ref().is_none_or(|id| *id == call.server_id)
+            && self.tool_name.as_ref().is_none_or(|name| *name == call.tool_name);
+        if applies {
+            for (key, value) in &self.defaults {
+                call.arguments
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        futures::future::ready(Ok(call)).boxed()
+    }
+}
+
+/// Shortens long text content in tool results
+pub struct ResultTruncation {
+    /// Maximum number of characters kept from each text content item
+    pub max_length: usize,
+}
+
+impl ToolInterceptor for ResultTruncation {
+    fn name(&self) -> &str {
+        "resultTruncation"
+    }
+
+    fn after_call(&self, _call: &ToolCall, mut result: Value) -> BoxFuture<'static, Result<Value>> {
+        if let Some(Value::Array(content)) = result.get_mut("content") {
+            for item in content {
+                if let Some(Value::String(text)) = item.get_mut("text") {
+                    let length = text.chars().count();
+                    if length > self.max_length {
+                        let kept: String = text.chars().take(self.max_length).collect();
+                        *text = format!(
+                            "{}\n[truncated {} characters]",
+                            kept,
+                            length - self.max_length
+                        );
+                    }
+                }
+            }
+        }
+        futures::future::ready(Ok(result)).boxed()
+    }
+}
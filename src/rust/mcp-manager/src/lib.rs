@@ -4,17 +4,63 @@
 //!
 //! This library provides a high-performance, production-ready solution for managing
 //! Model-Context-Protocol (MCP) servers from TypeScript applications.
+//!
+//! The core [`McpManager`] is a plain Rust API that reports events to an
+//! [`EventSink`]. The Node.js bindings are built with the `napi` feature,
+//! which is enabled by default; disable default features to use the crate
+//! from Rust alone.
 
+mod approval;
+mod client_handler;
+mod config_file;
+mod config_watch;
 mod error;
+mod event_buffer;
+mod events;
+mod hash;
+mod interceptor;
+mod limits;
+mod log_files;
+mod log_parser;
 mod manager;
 mod models;
+mod namespace;
+mod process_stats;
+mod redaction;
+mod replay;
+mod sandbox;
+mod schema;
+mod secrets;
+mod task;
+mod tool_filter;
+mod tool_format;
+mod trace;
+mod transport;
+#[cfg(feature = "napi")]
 mod wrapper;
 
 // Re-export the public API
-pub use error::error_codes;
-pub use models::{McpEvent, ServerConfig, ToolExecutionRequest, ToolInfo};
+pub use approval::ApprovalHandler;
+pub use config_file::{ServerDefinitions, load_server_definitions, parse_server_definitions};
+pub use error::{Error, Result, error_codes};
+pub use events::EventSink;
+pub use interceptor::{ArgumentDefaults, ResultTruncation, ToolCall, ToolInterceptor};
+pub use manager::McpManager;
+pub use secrets::{FileSecretProvider, SecretProvider};
+pub use models::{
+    ApplyAction, ApplyOptions, ApplyResult, ApprovalDecision, ApprovalPolicy, ApprovalRequest,
+    ConfigReloadedEvent, ConfigWatchOptions, Direction, EventFilter, EventQuery, InterceptedCall,
+    InterceptedResult, LimitAction, LimitBreach, LimitLevel, LimitStatus, LimitedResource, LogEvent,
+    ManagerOptions, McpEvent, McpEventType, MessageKind, NamespacePolicy, ProgressEvent, PromptArgumentInfo,
+    PromptInfo, ProviderTool, ReplayConfig, ReplayMatcher, ResourceInfo, ResourceLimitEvent,
+    ResourceLimits, SandboxConfig, SandboxNetwork, ServerConfig, ServerCrashedEvent, ServerInfo,
+    ServerStartedEvent, ServerStats, ServerStatsEvent, ServerStoppedEvent, StatsWatchOptions,
+    ToolAnnotations, ToolExecutionRequest, ToolFormat, ToolInfo, ToolsUpdatedEvent, TraceEntry,
+    TraceFormat, TraceQuery,
+};
 
 // Initialize logging when the library is loaded
+#[cfg(feature = "napi")]
 #[napi::module_init]
 fn init() {
     tracing_subscriber::fmt()
@@ -24,4 +70,5 @@ fn init() {
 }
 
 // Export the MCP manager wrapper
+#[cfg(feature = "napi")]
 pub use crate::wrapper::McpManagerWrapper;
\ No newline at end of file
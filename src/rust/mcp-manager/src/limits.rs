@@ -0,0 +1,165 @@
+use crate::error::{Error, Result};
+use crate::models::{
+    now_millis, LimitAction, LimitBreach, LimitLevel, LimitedResource, ResourceLimits, ServerStats,
+};
+use std::time::{Duration, Instant};
+
+/// How long CPU usage must stay at or above a CPU limit to reach it, when not configured
+pub const DEFAULT_CPU_WINDOW: Duration = Duration::from_secs(60);
+
+/// How often usage is compared against the limits, when not configured
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a server stopped for reaching a hard limit gets to exit after each step, when not configured
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Restarts for reaching a hard limit allowed within the restart window, when not configured
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Time over which restarts are counted against the maximum, when not configured
+pub const DEFAULT_RESTART_WINDOW: Duration = Duration::from_secs(300);
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Check that every soft limit is below its hard limit
+pub fn validate(limits: &ResourceLimits) -> Result<()> {
+    let pairs = [
+        ("memory", limits.soft_memory_mb, limits.hard_memory_mb),
+        ("CPU", limits.soft_cpu_percent, limits.hard_cpu_percent),
+    ];
+    for (resource, soft, hard) in pairs {
+        if let (Some(soft), Some(hard)) = (soft, hard) {
+            if soft > hard {
+                return Err(Error::ConfigError(format!(
+                    "Soft {} limit ({}) is above the hard limit ({})",
+                    resource, soft, hard
+                )));
+            }
+        }
+    }
+    if limits.check_interval_ms == Some(0) {
+        return Err(Error::ConfigError(
+            "Resource limit checkIntervalMs must be greater than 0".to_string(),
+        ));
+    }
+    if limits.restart_window_secs == Some(0) {
+        return Err(Error::ConfigError(
+            "Resource limit restartWindowSecs must be greater than 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Follows a server's usage across measurements to tell when it reaches its limits
+pub struct LimitTracker {
+    limits: ResourceLimits,
+    /// Whether the soft memory and CPU limits were reported, until usage drops below them again
+    memory_warned: bool,
+    cpu_warned: bool,
+    /// Since when CPU usage has stayed at or above the soft and hard CPU limits
+    cpu_soft_since: Option<Instant>,
+    cpu_hard_since: Option<Instant>,
+}
+
+impl LimitTracker {
+    pub fn new(limits: ResourceLimits) -> Self {
+        Self {
+            limits,
+            memory_warned: false,
+            cpu_warned: false,
+            cpu_soft_since: None,
+            cpu_hard_since: None,
+        }
+    }
+
+    /// Time between measurements
+    pub fn interval(&self) -> Duration {
+        self.limits
+            .check_interval_ms
+            .map_or(DEFAULT_CHECK_INTERVAL, |ms| Duration::from_millis(ms.into()))
+    }
+
+    /// What to do when a hard limit is reached
+    pub fn action(&self) -> LimitAction {
+        self.limits.action.unwrap_or(LimitAction::Restart)
+    }
+
+    /// How long the server gets to exit after its input is closed and after SIGTERM
+    pub fn grace_period(&self) -> Duration {
+        self.limits
+            .grace_period_ms
+            .map_or(DEFAULT_GRACE_PERIOD, |ms| Duration::from_millis(ms.into()))
+    }
+
+    /// Restarts allowed within the restart window
+    pub fn max_restarts(&self) -> u32 {
+        self.limits.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS)
+    }
+
+    /// Time over which restarts are counted against the maximum
+    pub fn restart_window(&self) -> Duration {
+        self.limits
+            .restart_window_secs
+            .map_or(DEFAULT_RESTART_WINDOW, |secs| Duration::from_secs(secs.into()))
+    }
+
+    /// Compare a measurement against the limits
+    ///
+    /// Returns the hard limits reached, if any, and otherwise the soft limits
+    /// reached since usage was last below them.
+    pub fn check(&mut self, stats: &ServerStats, now: Instant) -> Vec<LimitBreach> {
+        let memory = stats.memory_bytes / BYTES_PER_MB;
+        let cpu = stats.cpu_percent;
+        let window = self
+            .limits
+            .cpu_window_secs
+            .map_or(DEFAULT_CPU_WINDOW, |secs| Duration::from_secs(secs.into()));
+        let sustained = |since: &mut Option<Instant>, limit: Option<u32>| match limit {
+            Some(limit) if cpu >= f64::from(limit) => {
+                now.duration_since(*since.get_or_insert(now)) >= window
+            }
+            _ => {
+                *since = None;
+                false
+            }
+        };
+        let cpu_soft = sustained(&mut self.cpu_soft_since, self.limits.soft_cpu_percent);
+        let cpu_hard = sustained(&mut self.cpu_hard_since, self.limits.hard_cpu_percent);
+
+        let breach = |resource, level, value, limit: Option<u32>| LimitBreach {
+            resource,
+            level,
+            value,
+            limit: limit.map(f64::from).unwrap_or_default(),
+            timestamp: now_millis(),
+        };
+
+        let mut hard = Vec::new();
+        if matches!(self.limits.hard_memory_mb, Some(limit) if memory >= f64::from(limit)) {
+            let limit = self.limits.hard_memory_mb;
+            hard.push(breach(LimitedResource::Memory, LimitLevel::Hard, memory, limit));
+        }
+        if cpu_hard {
+            let limit = self.limits.hard_cpu_percent;
+            hard.push(breach(LimitedResource::Cpu, LimitLevel::Hard, cpu, limit));
+        }
+        if !hard.is_empty() {
+            return hard;
+        }
+
+        let mut soft = Vec::new();
+        let memory_soft = matches!(self.limits.soft_memory_mb, Some(limit) if memory >= f64::from(limit));
+        if memory_soft && !self.memory_warned {
+            let limit = self.limits.soft_memory_mb;
+            soft.push(breach(LimitedResource::Memory, LimitLevel::Soft, memory, limit));
+        }
+        self.memory_warned = memory_soft;
+        if cpu_soft && !self.cpu_warned {
+            let limit = self.limits.soft_cpu_percent;
+            soft.push(breach(LimitedResource::Cpu, LimitLevel::Soft, cpu, limit));
+        }
+        // Stays warned while CPU usage is above the limit but not yet for long enough again
+        self.cpu_warned = cpu_soft || (self.cpu_warned && self.cpu_soft_since.is_some());
+        soft
+    }
+}
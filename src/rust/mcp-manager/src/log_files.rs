@@ -0,0 +1,271 @@
+use crate::error::{Error, Result};
+use crate::hash::fnv1a;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use tracing::warn;
+
+/// Size at which a log file is rotated when not configured
+pub const DEFAULT_MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Number of rotated log files kept per server when not configured
+pub const DEFAULT_MAX_LOG_FILES: u32 = 3;
+
+/// An open log file and its current size
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/// Work for the writer thread
+enum Command {
+    /// Append a line to a server's log file
+    Write { server_id: String, line: String },
+    /// Answer once everything sent before is written
+    Flush(mpsc::SyncSender<()>),
+}
+
+/// Size-rotated log files, one set per server
+///
+/// Each server writes to `<dir>/<serverId>.log`. When the file would grow past
+/// the size limit it is renamed to `<serverId>.log.1`, older files shift up by
+/// one and the oldest beyond the configured count is deleted. Server IDs are
+/// made safe for file names, with a hash of the ID added when that changed it.
+///
+/// Lines are written by a thread of their own, so a slow disk never holds up
+/// the events being emitted.
+pub struct LogFiles {
+    /// Directory holding the log files
+    dir: PathBuf,
+    /// Number of rotated files kept per server
+    max_files: u32,
+    /// Queue of the writer thread
+    commands: mpsc::Sender<Command>,
+}
+
+impl LogFiles {
+    /// Create the log directory if needed and start the writer thread
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64, max_files: u32) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            Error::ConfigError(format!(
+                "Failed to create log directory '{}': {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let (commands, queue) = mpsc::channel();
+        let mut writer = Writer {
+            dir: dir.clone(),
+            max_size: max_size.max(1),
+            max_files,
+            files: HashMap::new(),
+        };
+        thread::Builder::new()
+            .name("mcp-log-writer".to_string())
+            .spawn(move || {
+                for command in queue {
+                    match command {
+                        Command::Write { server_id, line } => writer.write(&server_id, &line),
+                        Command::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .map_err(|e| Error::Other(format!("Failed to start the log writer: {}", e)))?;
+
+        Ok(Self {
+            dir,
+            max_files,
+            commands,
+        })
+    }
+
+    /// Queue a line for a server's log file
+    ///
+    /// Failures are only traced; losing a log line must never affect the server.
+    pub fn write(&self, server_id: &str, line: &str) {
+        let _ = self.commands.send(Command::Write {
+            server_id: server_id.to_string(),
+            line: line.to_string(),
+        });
+    }
+
+    /// Get the last lines written for a server, oldest first
+    ///
+    /// Waits until the lines queued so far are written.
+    pub fn tail(&self, server_id: &str, count: usize) -> Result<Vec<String>> {
+        let (done, flushed) = mpsc::sync_channel(1);
+        if self.commands.send(Command::Flush(done)).is_ok() {
+            let _ = flushed.recv();
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+
+        // Walk from the current file to the oldest rotated one until enough lines are found
+        for index in 0..=self.max_files {
+            if lines.len() >= count {
+                break;
+            }
+            let path = log_path(&self.dir, server_id, index);
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+                Err(e) => {
+                    return Err(Error::Other(format!(
+                        "Failed to read log file '{}': {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            };
+            let needed = count - lines.len();
+            let mut older: Vec<String> = content
+                .lines()
+                .rev()
+                .take(needed)
+                .map(str::to_string)
+                .collect();
+            older.reverse();
+            older.append(&mut lines);
+            lines = older;
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Owner of the open log files, living on the writer thread
+struct Writer {
+    /// Directory holding the log files
+    dir: PathBuf,
+    /// Size in bytes at which a file is rotated
+    max_size: u64,
+    /// Number of rotated files kept per server
+    max_files: u32,
+    /// Open files by server ID
+    files: HashMap<String, LogFile>,
+}
+
+impl Writer {
+    /// Append a line to a server's log file, rotating it when it is full
+    fn write(&mut self, server_id: &str, line: &str) {
+        let entry = format!("{}\n", line);
+
+        if let Some(current) = self.files.get(server_id) {
+            if current.size > 0 && current.size + entry.len() as u64 > self.max_size {
+                self.files.remove(server_id);
+                self.rotate(server_id);
+            }
+        }
+
+        if !self.files.contains_key(server_id) {
+            match self.open(server_id) {
+                Ok(file) => {
+                    self.files.insert(server_id.to_string(), file);
+                }
+                Err(e) => {
+                    warn!("Failed to open log file for server '{}': {}", server_id, e);
+                    return;
+                }
+            }
+        }
+
+        let current = self.files.get_mut(server_id).unwrap();
+        match current.file.write_all(entry.as_bytes()) {
+            Ok(()) => current.size += entry.len() as u64,
+            Err(e) => warn!("Failed to write log file for server '{}': {}", server_id, e),
+        }
+    }
+
+    /// Open a server's current log file for appending
+    fn open(&self, server_id: &str) -> std::io::Result<LogFile> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path(&self.dir, server_id, 0))?;
+        let size = file.metadata()?.len();
+        Ok(LogFile { file, size })
+    }
+
+    /// Shift a server's log files up by one, dropping the oldest
+    fn rotate(&self, server_id: &str) {
+        let path = |index| log_path(&self.dir, server_id, index);
+        if self.max_files == 0 {
+            let _ = fs::remove_file(path(0));
+            return;
+        }
+        let _ = fs::remove_file(path(self.max_files));
+        for index in (0..self.max_files).rev() {
+            let from = path(index);
+            if from.exists() {
+                if let Err(e) = fs::rename(&from, path(index + 1)) {
+                    warn!("Failed to rotate log file '{}': {}", from.display(), e);
+                }
+            }
+        }
+    }
+}
+
+/// Path of a server's log file; index 0 is the current file
+///
+/// IDs that are not safe as file names get `~` and a hash of the ID appended,
+/// so IDs that only differ in such characters keep separate files.
+fn log_path(dir: &Path, server_id: &str, index: u32) -> PathBuf {
+    let mut name: String = server_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name != server_id {
+        name = format!("{}~{:08x}", name, fnv1a(server_id.as_bytes()));
+    }
+    match index {
+        0 => dir.join(format!("{}.log", name)),
+        index => dir.join(format!("{}.log.{}", name, index)),
+    }
+}
+
+/// Format milliseconds since the Unix epoch as an RFC 3339 UTC timestamp
+pub fn format_timestamp(millis: i64) -> String {
+    let seconds = millis.div_euclid(1000);
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        millis.rem_euclid(1000)
+    )
+}
@@ -0,0 +1,273 @@
+use crate::error::{Error, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+
+lazy_static! {
+    /// Level keyword near the start of a line, optionally after a timestamp or bracket
+    static ref LEVEL_KEYWORD: Regex = Regex::new(
+        r"(?i)^\W{0,3}(?:[\d:.\-/TZ+]+\s+){0,2}\W{0,2}(trace|verbose|debug|info|notice|warn|warning|error|err|fatal|critical|crit|panic)\b"
+    )
+    .unwrap();
+    /// Header line of an error whose stack trace may follow, e.g. `TypeError: boom`
+    static ref ERROR_HEADER: Regex =
+        Regex::new(r"^(?:Uncaught\s+)?[\w.]*(?:Error|Exception)\b").unwrap();
+    /// Stack frame line printed by Node.js
+    static ref NODE_FRAME: Regex = Regex::new(r"^\s+at\s").unwrap();
+}
+
+/// First line of a Python traceback
+const PYTHON_TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+
+/// Lines Python prints between the tracebacks of chained exceptions
+const PYTHON_CHAIN_MESSAGES: [&str; 2] = [
+    "During handling of the above exception, another exception occurred:",
+    "The above exception was the direct cause of the following exception:",
+];
+
+/// A log entry recovered from one or more stderr lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedLog {
+    /// Normalized log level (`trace`, `debug`, `info`, `warn` or `error`)
+    pub level: String,
+    /// Log message, possibly spanning several lines
+    pub message: String,
+}
+
+/// How far a pending entry got into a Python traceback
+#[derive(Clone, Copy, PartialEq)]
+enum Traceback {
+    /// The entry is no Python traceback
+    None,
+    /// Stack frames are being read
+    Frames,
+    /// The exception line was read; the traceback of a chained exception may follow
+    Raised,
+    /// A chain message was read, announcing the traceback of another exception
+    Chained,
+}
+
+/// Entry that may still receive continuation lines
+struct Pending {
+    level: String,
+    lines: Vec<String>,
+    traceback: Traceback,
+    /// Lines after a Python exception that only belong to the entry if a chained traceback follows
+    held: Vec<String>,
+}
+
+/// Turns stderr lines of a server into leveled log entries
+///
+/// Lines are matched against the server's level pattern first, then parsed as
+/// JSON logs, then checked for level keywords. Python tracebacks, chained ones
+/// included, and Node.js stack traces are grouped into a single entry, so every
+/// entry is held back until the next line shows whether it continues; callers
+/// should call [`StderrParser::flush`] when no line arrived for a short while.
+pub struct StderrParser {
+    /// Pattern with a `level` capture group configured for the server
+    pattern: Option<Regex>,
+    /// Entry waiting for possible continuation lines
+    pending: Option<Pending>,
+}
+
+impl StderrParser {
+    /// Create a parser, optionally using a server-specific level pattern
+    pub fn new(pattern: Option<&str>) -> Result<Self> {
+        let pattern = match pattern {
+            Some(pattern) => {
+                let regex = Regex::new(pattern).map_err(|e| {
+                    Error::ConfigError(format!("Invalid log level pattern '{}': {}", pattern, e))
+                })?;
+                if !regex.capture_names().any(|name| name == Some("level")) {
+                    return Err(Error::ConfigError(format!(
+                        "Log level pattern '{}' has no 'level' capture group",
+                        pattern
+                    )));
+                }
+                Some(regex)
+            }
+            None => None,
+        };
+        Ok(Self {
+            pattern,
+            pending: None,
+        })
+    }
+
+    /// Whether an entry is waiting for continuation lines
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Feed one stderr line, returning the entries it completed
+    pub fn push_line(&mut self, line: &str) -> Vec<ParsedLog> {
+        let mut completed = Vec::new();
+
+        if let Some(pending) = &mut self.pending {
+            match pending.traceback {
+                Traceback::Frames => {
+                    pending.lines.push(line.to_string());
+                    // The exception itself is the first unindented line after the frames
+                    if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+                        pending.traceback = Traceback::Raised;
+                    }
+                    return completed;
+                }
+                Traceback::Raised | Traceback::Chained => {
+                    let trimmed = line.trim_end();
+                    if trimmed.is_empty() || PYTHON_CHAIN_MESSAGES.contains(&trimmed) {
+                        if !trimmed.is_empty() {
+                            pending.traceback = Traceback::Chained;
+                        }
+                        pending.held.push(line.to_string());
+                        return completed;
+                    }
+                    if pending.traceback == Traceback::Chained
+                        && trimmed == PYTHON_TRACEBACK_HEADER
+                    {
+                        let held = std::mem::take(&mut pending.held);
+                        pending.lines.extend(held);
+                        pending.lines.push(line.to_string());
+                        pending.traceback = Traceback::Frames;
+                        return completed;
+                    }
+                    // No chained traceback follows, so the held lines are entries of their own
+                    let held = std::mem::take(&mut pending.held);
+                    completed.extend(self.flush());
+                    for held_line in held.iter().map(String::as_str).chain([line]) {
+                        completed.extend(self.push_line(held_line));
+                    }
+                    return completed;
+                }
+                Traceback::None => {}
+            }
+            if NODE_FRAME.is_match(line) {
+                pending.lines.push(line.to_string());
+                pending.level = "error".to_string();
+                return completed;
+            }
+            completed.extend(self.flush());
+        }
+
+        self.pending = Some(if line.trim_end() == PYTHON_TRACEBACK_HEADER {
+            Pending {
+                level: "error".to_string(),
+                lines: vec![line.to_string()],
+                traceback: Traceback::Frames,
+                held: Vec::new(),
+            }
+        } else {
+            let (level, message) = self.classify(line);
+            Pending {
+                level,
+                lines: vec![message],
+                traceback: Traceback::None,
+                held: Vec::new(),
+            }
+        });
+        completed
+    }
+
+    /// Complete the pending entry, if any
+    ///
+    /// Lines held back after a Python exception end the entry, blank ones dropped.
+    pub fn flush(&mut self) -> Option<ParsedLog> {
+        self.pending.take().map(|mut pending| {
+            let kept = pending.held.iter().rposition(|line| !line.trim().is_empty());
+            pending.lines.extend(pending.held.into_iter().take(kept.map_or(0, |i| i + 1)));
+            ParsedLog {
+                level: pending.level,
+                message: pending.lines.join("\n"),
+            }
+        })
+    }
+
+    /// Determine the level and message of a single line
+    fn classify(&self, line: &str) -> (String, String) {
+        if let Some(level) = self
+            .pattern
+            .as_ref()
+            .and_then(|pattern| pattern.captures(line))
+            .and_then(|captures| captures.name("level"))
+            .and_then(|level| normalize_level(level.as_str()))
+        {
+            return (level.to_string(), line.to_string());
+        }
+
+        if let Some(parsed) = parse_json_line(line) {
+            return parsed;
+        }
+
+        if let Some(level) = LEVEL_KEYWORD
+            .captures(line)
+            .and_then(|captures| normalize_level(&captures[1]))
+        {
+            return (level.to_string(), line.to_string());
+        }
+
+        if ERROR_HEADER.is_match(line) {
+            return ("error".to_string(), line.to_string());
+        }
+
+        ("info".to_string(), line.to_string())
+    }
+}
+
+/// Parse a JSON-lines log entry with a level field
+fn parse_json_line(line: &str) -> Option<(String, String)> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let Value::Object(entry) = serde_json::from_str::<Value>(line).ok()? else {
+        return None;
+    };
+
+    let level = ["level", "severity", "lvl", "levelname"]
+        .iter()
+        .find_map(|key| entry.get(*key))
+        .and_then(|level| match level {
+            Value::String(level) => normalize_level(level),
+            // Numeric levels as used by pino and bunyan
+            Value::Number(level) => level.as_u64().map(|level| match level {
+                0..=19 => "trace",
+                20..=29 => "debug",
+                30..=39 => "info",
+                40..=49 => "warn",
+                _ => "error",
+            }),
+            _ => None,
+        })?;
+
+    let message = ["msg", "message"]
+        .iter()
+        .find_map(|key| entry.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| line.to_string());
+
+    Some((level.to_string(), message))
+}
+
+/// Map the many spellings of log levels onto the levels used in log events
+pub fn normalize_level(level: &str) -> Option<&'static str> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" | "verbose" => Some("trace"),
+        "debug" => Some("debug"),
+        "info" | "notice" | "log" => Some("info"),
+        "warn" | "warning" => Some("warn"),
+        "error" | "err" | "fatal" | "critical" | "crit" | "panic" | "alert" | "emergency" => {
+            Some("error")
+        }
+        _ => None,
+    }
+}
+
+/// Order log levels by severity so they can be compared against a minimum
+pub fn level_rank(level: &str) -> u8 {
+    match normalize_level(level) {
+        Some("trace") => 0,
+        Some("debug") => 1,
+        Some("warn") => 3,
+        Some("error") => 4,
+        _ => 2,
+    }
+}
@@ -1,50 +1,205 @@
+use crate::approval::{ApprovalGate, ApprovalHandler};
+use crate::client_handler::{self, McpClientHandler, ToolsChangedHook};
+use crate::config_file;
+use crate::config_watch::{self, ConfigWatcher};
 use crate::error::{Error, Result};
-use crate::models::{McpEvent, ServerConfig, ToolExecutionRequest, ToolInfo};
+use crate::event_buffer::{DEFAULT_EVENT_BUFFER_SIZE, DEFAULT_SERVER_EVENT_BUFFER_SIZE, EventBuffer};
+use crate::events::{EventEmitter, EventSink};
+use crate::hash::fnv1a_64;
+use crate::log_files::{DEFAULT_MAX_LOG_FILES, DEFAULT_MAX_LOG_FILE_SIZE, LogFiles};
+use crate::log_parser::{ParsedLog, StderrParser};
+use crate::interceptor::{InterceptorChain, ToolCall, ToolInterceptor};
+use crate::limits::{self, LimitTracker};
+use crate::models::{
+    ApplyAction, ApplyOptions, ApplyResult, ApprovalPolicy, ApprovalRequest, ConfigWatchOptions,
+    EventFilter, EventQuery, LimitAction, LimitBreach, LimitLevel, LimitStatus, LimitedResource,
+    ManagerOptions, McpEvent, NamespacePolicy, PromptArgumentInfo, PromptInfo, ProviderTool, ResourceInfo,
+    ResourceLimits, ServerConfig, ServerInfo, ServerStats, StatsWatchOptions, ToolExecutionRequest,
+    ToolFormat, ToolInfo, TraceEntry, TraceFormat, TraceQuery,
+};
+use crate::namespace::{self, QualifiedTools};
+use crate::process_stats::{self, ProcessMonitor};
+use crate::tool_filter::ToolFilter;
+use crate::tool_format;
+use crate::redaction::{self, Redactor};
+use crate::replay::{Recorder, ReplayTransport};
+use crate::sandbox::Sandbox;
+use crate::secrets::{self, SecretProvider};
+use crate::task::AbortOnDrop;
+use crate::trace::{DEFAULT_TRACE_BUFFER_SIZE, Tracer};
+use crate::transport::{ClosableWriter, InputCloser, MessageObserver, ObservedTransport};
 use dashmap::DashMap;
-use napi::threadsafe_function::ThreadsafeFunction;
-use rmcp::transport::TokioChildProcess;
+use futures::StreamExt;
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParam, ClientRequest, Meta, NumberOrString, ProgressToken,
+    ServerResult, SetLevelRequestParam, Tool,
+};
+use rmcp::service::{Peer, PeerRequestOptions, RoleClient, ServiceError};
+use rmcp::transport::async_rw::TransportAdapterAsyncRW;
+use rmcp::transport::{IntoTransport, TokioChildProcess, Transport};
 
-use tokio::process::{Child, Command};
+use tokio::process::{ChildStderr, Command};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::process::Stdio;
-use std::sync::Arc;
-use sysinfo::{Pid, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessStatus, Signal};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::Mutex;
 use tracing::{debug, warn};
 use rmcp::transport::ConfigureCommandExt;
 
+/// Running MCP client session with a server
+type McpService = rmcp::service::RunningService<rmcp::service::RoleClient, McpClientHandler>;
+
+/// How long stderr output may pause before a multi-line log entry is considered complete
+const STDERR_FLUSH_DELAY: Duration = Duration::from_millis(100);
+
+/// How often a server being stopped is checked for having exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Number of servers `apply` starts or stops at the same time when not configured
+const DEFAULT_APPLY_CONCURRENCY: usize = 4;
+
 struct Server {
-    /// The child process running the server
-    process: Arc<Mutex<Child>>,
+    /// Configuration the server was started with
+    config: ServerConfig,
+    /// Hash of `config`, used to tell whether a desired configuration differs
+    config_hash: String,
+    /// Position of the server in start order, used to resolve name collisions
+    order: u64,
     /// The MCP client connected to the server
-    client: Arc<Mutex<Option<rmcp::service::RunningService<rmcp::service::RoleClient, ()>>>>,
-    /// The PID of the server process
-    pid: u32,
-    /// Tools provided by this server
+    client: Arc<Mutex<Option<McpService>>>,
+    /// The PID of the server process, unless a recording is played back
+    pid: Option<u32>,
+    /// Closes the server process's stdin, asking it to exit
+    input: Option<InputCloser>,
+    /// Tools provided by this server, after filtering and aliasing
     tools: Vec<ToolInfo>,
+    /// Map of exposed tool name to the name the server knows the tool by
+    original_names: HashMap<String, String>,
+    /// Compares the server's usage against its configured resource limits
+    _limit_watch: Option<AbortOnDrop>,
+}
+
+/// Resource limits a server reached, kept after a hard limit stops it
+#[derive(Default)]
+struct LimitHistory {
+    /// Number of times the server was restarted for reaching a hard resource limit
+    restarts: u32,
+    /// When the server was restarted for reaching a hard resource limit, within the restart window
+    restart_times: Vec<std::time::Instant>,
+    /// Most recent resource limit the server reached
+    last_breach: Option<LimitBreach>,
+    /// Whether a hard limit stopped the server, which was not started again since
+    stopped: bool,
 }
 
 /// Manager for MCP servers
 pub struct McpManager {
+    /// The manager itself when created by `new_shared`, for background tasks that restart servers
+    this: Weak<Self>,
     /// Map of server ID to server instance
-    servers: DashMap<String, Server>,
-    /// Callback function for events
-    event_callback: ThreadsafeFunction<McpEvent>,
-    /// System information for process management
-    system: Arc<Mutex<System>>,
+    servers: Arc<DashMap<String, Server>>,
+    /// Destination for events
+    events: EventEmitter,
+    /// Process information for stopping process trees and measuring their resource usage
+    processes: Arc<Mutex<ProcessMonitor>>,
+    /// How tool names are made unique across servers
+    namespace_policy: Arc<RwLock<NamespacePolicy>>,
+    /// Counter used to record the start order of servers
+    next_order: AtomicU64,
+    /// Approval policies and handler consulted before tool calls
+    approvals: ApprovalGate,
+    /// Interceptors applied around every tool call
+    interceptors: InterceptorChain,
+    /// Captured JSON-RPC traffic of traced servers
+    tracer: Arc<Tracer>,
+    /// Reloads the config file passed to `watch_config` when it changes
+    config_watch: std::sync::Mutex<Option<AbortOnDrop>>,
+    /// Resolves `${secret:key}` references in server env values
+    secret_provider: RwLock<Option<Arc<dyn SecretProvider>>>,
+    /// Emits `serverStats` events started by `watch_stats`
+    stats_watch: std::sync::Mutex<Option<AbortOnDrop>>,
+    /// Map of server ID to the resource limits the server reached, until it is stopped by `stop`
+    limit_history: DashMap<String, LimitHistory>,
 }
 
 impl McpManager {
     /// Create a new MCP manager
-    pub fn new(event_callback: ThreadsafeFunction<McpEvent>) -> Self {
-        let mut system = System::new();
-        system.refresh_all();
-
-        Self {
-            servers: DashMap::new(),
-            event_callback: event_callback,
-            system: Arc::new(Mutex::new(system)),
-        }
+    pub fn new(
+        event_sink: Arc<dyn EventSink>,
+        options: ManagerOptions,
+    ) -> Result<Self> {
+        let log_files = match &options.log_dir {
+            Some(dir) => Some(LogFiles::new(
+                dir,
+                options
+                    .max_log_file_size
+                    .map(u64::from)
+                    .unwrap_or(DEFAULT_MAX_LOG_FILE_SIZE),
+                options.max_log_files.unwrap_or(DEFAULT_MAX_LOG_FILES),
+            )?),
+            None => None,
+        };
+
+        let redactor = Redactor::new(
+            options.default_redaction_patterns.unwrap_or(true),
+            options.redaction_patterns.as_deref().unwrap_or_default(),
+        )?;
+
+        let tracer = Arc::new(Tracer::new(
+            options
+                .trace_buffer_size
+                .map_or(DEFAULT_TRACE_BUFFER_SIZE, |size| size as usize),
+        ));
+
+        Ok(Self {
+            this: Weak::new(),
+            servers: Arc::new(DashMap::new()),
+            events: EventEmitter::new(
+                event_sink,
+                EventBuffer::new(
+                    options
+                        .event_buffer_size
+                        .map_or(DEFAULT_EVENT_BUFFER_SIZE, |size| size as usize),
+                    options
+                        .server_event_buffer_size
+                        .map_or(DEFAULT_SERVER_EVENT_BUFFER_SIZE, |size| size as usize),
+                ),
+                tracer.clone(),
+                log_files,
+                options.log_traffic.unwrap_or(false),
+                redactor,
+            ),
+            processes: Arc::new(Mutex::new(ProcessMonitor::new())),
+            namespace_policy: Arc::new(RwLock::new(NamespacePolicy::ServerId)),
+            next_order: AtomicU64::new(0),
+            approvals: ApprovalGate::new(),
+            interceptors: InterceptorChain::new(),
+            tracer,
+            config_watch: std::sync::Mutex::new(None),
+            stats_watch: std::sync::Mutex::new(None),
+            secret_provider: RwLock::new(None),
+            limit_history: DashMap::new(),
+        })
+    }
+
+    /// Create a new MCP manager that can restart servers on its own
+    ///
+    /// Resource limits are only enforced by managers created this way, as a
+    /// server reaching a hard limit may have to be started again.
+    pub fn new_shared(
+        event_sink: Arc<dyn EventSink>,
+        options: ManagerOptions,
+    ) -> Result<Arc<Self>> {
+        let manager = Self::new(event_sink, options)?;
+        Ok(Arc::new_cyclic(|this| Self {
+            this: this.clone(),
+            ..manager
+        }))
     }
 
     /// Start a new MCP server
@@ -57,92 +212,195 @@ impl McpManager {
             )));
         }
 
+        let filter = Arc::new(ToolFilter::from_config(&config)?);
+        if let Some(limits) = &config.limits {
+            limits::validate(limits)?;
+            if self.this.upgrade().is_none() {
+                return Err(Error::ConfigError(format!(
+                    "Server '{}' has resource limits, which only managers created with `new_shared` enforce",
+                    config.id
+                )));
+            }
+        }
+        let log_level = config
+            .log_level
+            .as_deref()
+            .map(|level| {
+                client_handler::parse_log_level(level)
+                    .ok_or_else(|| Error::ConfigError(format!("Invalid log level '{}'", level)))
+            })
+            .transpose()?;
+        let stderr_parser = StderrParser::new(config.log_level_pattern.as_deref())?;
+
         // Emit info event
         self.emit_event(McpEvent::info(
             &format!("Starting server '{}'...", config.id),
             Some(&config.id),
         ));
 
-        // Create command
-        let mut command = Command::new(&config.command);
-        command
-            .args(&config.args)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // Start the process
-        let mut child = command.spawn().map_err(|e| {
-            Error::ProcessStartError(format!("Failed to start server process: {}", e))
-        })?;
+        let order = self.next_order.fetch_add(1, Ordering::SeqCst);
+        let handler = McpClientHandler::new(
+            config.id.clone(),
+            self.events.clone(),
+            self.tools_changed_hook(&config.id, order, filter.clone()),
+        );
+        let (service, pid, input) = match &config.replay {
+            // A recording stands in for the server, so there is no process to watch
+            Some(replay) => {
+                let transport = ReplayTransport::open(replay)?;
+                (self.connect(&config, handler, transport).await?, None, None)
+            }
+            None => {
+                // Secrets are resolved right before spawning and only kept to be masked
+                let (env, secret_values) = match &config.env {
+                    Some(env) => {
+                        let provider = self.secret_provider.read().unwrap().clone();
+                        let (env, mut values) = secrets::resolve_env(env, provider).await?;
+                        values.extend(
+                            env.iter()
+                                .filter(|(name, _)| redaction::is_sensitive_name(name))
+                                .map(|(_, value)| value.clone()),
+                        );
+                        (Some(env), values)
+                    }
+                    None => (None, Vec::new()),
+                };
 
-        // Set up stderr logging
-        if let Some(stderr) = child.stderr.take() {
-            let server_id = config.id.clone();
-            let event_callback = self.event_callback.clone();
-            
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                let mut lines = reader.lines();
-                
-                while let Some(line) = lines.next_line().await.unwrap_or(None) {
-                    let event = McpEvent::log("info", &line, Some(&server_id));
-                    event_callback.call(Ok(event), napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking);
+                let sandbox = config
+                    .sandbox
+                    .as_ref()
+                    .map(|sandbox| Sandbox::prepare(sandbox, &config.command))
+                    .transpose()?;
+                if let Some(warning) = sandbox.as_ref().and_then(Sandbox::warning) {
+                    self.emit_event(McpEvent::warn(warning, Some(&config.id)));
                 }
-            });
-        }
 
-        // Create MCP client
-        let transport = TokioChildProcess::new(Command::new(&config.command).configure(|cmd| {
-            cmd.args(&config.args);
-        }))
-        .map_err(|e| {
-            Error::ProcessStartError(format!("Failed to create MCP transport: {}", e))
-        })?;
+                // Start the process with stderr captured for logging
+                let (transport, stderr) = TokioChildProcess::builder(
+                    Command::new(&config.command).configure(|cmd| {
+                        cmd.args(&config.args);
+                        if let Some(env) = &env {
+                            cmd.envs(env);
+                        }
+                        #[cfg(target_os = "linux")]
+                        if let Some(sandbox) = sandbox.clone() {
+                            // SAFETY: the hook only makes async-signal-safe system calls
+                            unsafe {
+                                cmd.pre_exec(move || sandbox.apply());
+                            }
+                        }
+                    }),
+                )
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| {
+                    if sandbox.is_some() {
+                        Error::ProcessStartError(format!(
+                            "Failed to start server process in its sandbox: {}",
+                            e
+                        ))
+                    } else {
+                        Error::ProcessStartError(format!("Failed to start server process: {}", e))
+                    }
+                })?;
 
-        // Initialize MCP service
-        let service = rmcp::service::serve_client((), transport).await.map_err(|e| {
-            Error::CommunicationError(format!("Failed to initialize MCP service: {}", e))
-        })?;
+                let pid = transport.id().ok_or_else(|| {
+                    Error::ProcessStartError("Failed to get process ID".to_string())
+                })?;
+
+                // Masked from before the process's output is read until it ended
+                self.events.redactor().set_values(&config.id, order, secret_values);
+
+                // Set up stderr logging, which also notices when the process goes away
+                if let Some(stderr) = stderr {
+                    self.forward_stderr(config.id.clone(), order, stderr, stderr_parser);
+                }
+
+                // Stdin stays closable on its own so the server can be asked to exit
+                let (stdout, stdin) = transport.split();
+                let (stdin, input) = ClosableWriter::new(stdin);
+                let transport = IntoTransport::<RoleClient, std::io::Error, TransportAdapterAsyncRW>::into_transport(
+                    (stdout, stdin),
+                );
+                (self.connect(&config, handler, transport).await?, Some(pid), Some(input))
+            }
+        };
 
         // Get server info
         let server_info = service.peer_info();
         debug!("Connected to server: {:?}", server_info);
 
+        // Ask for the configured amount of MCP log messages
+        if let Some(level) = log_level {
+            let supports_logging = server_info
+                .map(|info| info.capabilities.logging.is_some())
+                .unwrap_or(false);
+            if supports_logging {
+                if let Err(e) = service.set_level(SetLevelRequestParam { level }).await {
+                    self.emit_event(McpEvent::warn(
+                        &format!("Failed to set log level: {}", e),
+                        Some(&config.id),
+                    ));
+                }
+            } else {
+                self.emit_event(McpEvent::warn(
+                    "Server does not support MCP logging; ignoring configured log level",
+                    Some(&config.id),
+                ));
+            }
+        }
+
         // Get available tools
         let tools_result = service.list_tools(Default::default()).await.map_err(|e| {
             Error::CommunicationError(format!("Failed to list tools: {}", e))
         })?;
 
-        // Convert tools to our format
-        let tools = tools_result
-            .tools
-            .into_iter()
-            .map(|tool| ToolInfo {
-                server_id: config.id.clone(),
-                tool_name: tool.name.to_string(),
-                description: tool.description.unwrap_or_default().to_string(),
-                parameters: serde_json::to_string(&tool.input_schema).unwrap_or_default(),
-            })
-            .collect::<Vec<_>>();
+        let tool_names: Vec<&str> = tools_result.tools.iter().map(|tool| &*tool.name).collect();
+        filter.check_aliases(&config.id, &tool_names)?;
+
+        let (tools, original_names) = expose_tools(&config.id, &filter, tools_result.tools);
+
+        let limit_watch = pid
+            .zip(config.limits.clone())
+            .map(|(pid, limits)| self.watch_limits(&config.id, pid, limits));
 
         // Store server
-        let pid = child.id().ok_or_else(|| {
-            Error::ProcessStartError("Failed to get process ID".to_string())
-        })?;
-        
         let server = Server {
-            process: Arc::new(Mutex::new(child)),
+            config_hash: config_hash(&config),
+            config: config.clone(),
+            order,
             client: Arc::new(Mutex::new(Some(service))),
             pid,
-            tools: tools.clone(),
+            input,
+            tools,
+            original_names,
+            _limit_watch: limit_watch,
         };
 
         self.servers.insert(config.id.clone(), server);
+        if let Some(mut history) = self.limit_history.get_mut(&config.id) {
+            history.stopped = false;
+        }
+
+        // Resolve qualified names now that the server's tools are known
+        let QualifiedTools { tools, shadowed } = self.qualified_tools();
+        for tool in shadowed.iter().filter(|t| t.server_id == config.id) {
+            self.emit_event(McpEvent::warn(
+                &format!(
+                    "Tool '{}' is hidden because another server already provides '{}'",
+                    tool.tool_name, tool.qualified_name
+                ),
+                Some(&config.id),
+            ));
+        }
+        let tools = tools
+            .into_iter()
+            .filter(|t| t.server_id == config.id)
+            .collect();
 
         // Emit events
         self.emit_event(McpEvent::server_started(&config.id));
-        self.emit_event(McpEvent::tools_updated(tools));
+        self.emit_event(McpEvent::tools_updated(&config.id, tools));
         self.emit_event(McpEvent::info(
             &format!("Server '{}' started successfully", config.id),
             Some(&config.id),
@@ -153,6 +411,13 @@ impl McpManager {
 
     /// Stop an MCP server
     pub async fn stop(&self, server_id: &str) -> Result<()> {
+        self.stop_server(server_id, None).await?;
+        self.limit_history.remove(server_id);
+        Ok(())
+    }
+
+    /// Stop a server, killing its processes right away or, given a grace period, asking them to exit first
+    async fn stop_server(&self, server_id: &str, grace_period: Option<Duration>) -> Result<()> {
         // Check if server exists
         let server = match self.servers.remove(server_id) {
             Some(server_entry) => server_entry.1,
@@ -164,6 +429,15 @@ impl McpManager {
             Some(server_id),
         ));
 
+        // Kill process and all descendants while they are still linked to the server process
+        let killed = match (server.pid, grace_period) {
+            (Some(pid), Some(grace_period)) => {
+                self.stop_process_tree(pid, server.input.as_ref(), grace_period).await
+            }
+            (Some(pid), None) => self.kill_process_tree(pid as i32).await,
+            (None, _) => Ok(()),
+        };
+
         // Cancel MCP service
         {
             let mut client_guard = server.client.lock().await;
@@ -174,9 +448,7 @@ impl McpManager {
                 }
             }
         }
-
-        // Kill process and all descendants
-        self.kill_process_tree(server.pid as i32).await?;
+        killed?;
 
         // Emit events
         self.emit_event(McpEvent::server_stopped(server_id));
@@ -188,19 +460,388 @@ impl McpManager {
         Ok(())
     }
 
+    /// Start the servers defined by a config file (a path or inline JSON) and stop all others
+    pub async fn start_from_config(&self, source: &str) -> Result<Vec<ApplyResult>> {
+        let definitions = config_file::load_server_definitions(source)?;
+        for (id, reason) in &definitions.skipped {
+            self.emit_event(McpEvent::warn(
+                &format!("Skipping server '{}': {}", id, reason),
+                Some(id),
+            ));
+        }
+        self.apply(definitions.servers, ApplyOptions::default()).await
+    }
+
+    /// Start the servers defined by a config file and keep them in line with the file as it changes
+    ///
+    /// Once the file has stayed unchanged for the debounce period, it is applied like
+    /// `start_from_config` and the outcome is reported by a `configReloaded` event. An
+    /// invalid file is reported without touching any server. Replaces any earlier watch.
+    pub async fn watch_config(
+        self: &Arc<Self>,
+        path: &str,
+        options: ConfigWatchOptions,
+    ) -> Result<Vec<ApplyResult>> {
+        if path.trim_start().starts_with('{') {
+            return Err(Error::ConfigError(
+                "Only config files can be watched, not inline JSON".to_string(),
+            ));
+        }
+        let debounce = options
+            .debounce_ms
+            .map_or(config_watch::DEFAULT_DEBOUNCE, |ms| Duration::from_millis(ms.into()));
+        // Watch before the first load so edits made while it runs are not missed
+        let mut watcher = ConfigWatcher::new(Path::new(path), debounce)?;
+        let results = self.start_from_config(path).await?;
+
+        let manager = Arc::downgrade(self);
+        let path = path.to_string();
+        let task = tokio::spawn(async move {
+            while watcher.changed().await {
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.reload_config(&path).await;
+            }
+        });
+        *self.config_watch.lock().unwrap() = Some(AbortOnDrop::new(task));
+        Ok(results)
+    }
+
+    /// Stop watching the config file passed to `watch_config`; running servers are kept
+    pub fn unwatch_config(&self) {
+        self.config_watch.lock().unwrap().take();
+    }
+
+    /// Apply a watched config file again and report the outcome
+    async fn reload_config(&self, path: &str) {
+        let event = match self.start_from_config(path).await {
+            Ok(results) => {
+                let changes = results
+                    .into_iter()
+                    .filter(|result| result.action != ApplyAction::Unchanged)
+                    .collect();
+                McpEvent::config_reloaded(path, changes, None)
+            }
+            Err(e) => {
+                self.emit_event(McpEvent::error(
+                    &format!("Keeping running servers, config '{}' is invalid: {}", path, e),
+                    None,
+                ));
+                McpEvent::config_reloaded(path, Vec::new(), Some(e.to_string()))
+            }
+        };
+        self.emit_event(event);
+    }
+
+    /// Bring the running servers in line with a desired set
+    ///
+    /// Servers missing from `configs` are stopped, new ones are started and those
+    /// whose configuration hash changed are restarted, several at a time. A server
+    /// that fails is reported in its result rather than failing the whole call.
+    /// Results follow the order of `configs`, followed by the stopped servers.
+    pub async fn apply(
+        &self,
+        configs: Vec<ServerConfig>,
+        options: ApplyOptions,
+    ) -> Result<Vec<ApplyResult>> {
+        let mut wanted = HashSet::new();
+        for config in &configs {
+            if !wanted.insert(config.id.clone()) {
+                return Err(Error::ConfigError(format!(
+                    "Server '{}' is listed more than once",
+                    config.id
+                )));
+            }
+        }
+
+        let running: HashMap<String, String> = self
+            .servers
+            .iter()
+            .map(|server| (server.key().clone(), server.config_hash.clone()))
+            .collect();
+        let removed: Vec<String> = running
+            .keys()
+            .filter(|id| !wanted.contains(*id))
+            .cloned()
+            .collect();
+
+        let concurrency = options
+            .concurrency
+            .map_or(DEFAULT_APPLY_CONCURRENCY, |limit| limit.max(1) as usize);
+        let changes = configs
+            .into_iter()
+            .map(|config| (config.id.clone(), Some(config)))
+            .chain(removed.into_iter().map(|id| (id, None)));
+        let results = futures::stream::iter(changes)
+            .map(|(id, config)| {
+                let running_hash = running.get(&id).cloned();
+                self.apply_one(id, config, running_hash)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+        Ok(results)
+    }
+
+    /// Start, stop or restart one server so it matches its desired configuration
+    async fn apply_one(
+        &self,
+        server_id: String,
+        config: Option<ServerConfig>,
+        running_hash: Option<String>,
+    ) -> ApplyResult {
+        let failed = |error: Error| ApplyResult {
+            server_id: server_id.clone(),
+            action: ApplyAction::Failed,
+            config_hash: None,
+            error: Some(error.to_string()),
+        };
+
+        let Some(config) = config else {
+            return match self.stop(&server_id).await {
+                Ok(()) => ApplyResult {
+                    server_id: server_id.clone(),
+                    action: ApplyAction::Stopped,
+                    config_hash: None,
+                    error: None,
+                },
+                Err(e) => failed(e),
+            };
+        };
+
+        let hash = config_hash(&config);
+        let action = match running_hash {
+            Some(running_hash) if running_hash == hash => ApplyAction::Unchanged,
+            Some(_) => {
+                if let Err(e) = self.stop(&server_id).await {
+                    return failed(e);
+                }
+                ApplyAction::Restarted
+            }
+            None => ApplyAction::Started,
+        };
+        if action != ApplyAction::Unchanged {
+            if let Err(e) = self.start(config).await {
+                return failed(e);
+            }
+        }
+
+        ApplyResult {
+            server_id: server_id.clone(),
+            action,
+            config_hash: Some(hash),
+            error: None,
+        }
+    }
+
+    /// Initialize the MCP service, observing the JSON-RPC traffic if it is traced, recorded or logged
+    async fn connect<T>(
+        &self,
+        config: &ServerConfig,
+        handler: McpClientHandler,
+        transport: T,
+    ) -> Result<McpService>
+    where
+        T: Transport<RoleClient> + Send + 'static,
+        T::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let trace = config.trace.unwrap_or(false);
+        let recorder = match &config.record {
+            Some(path) => Some(Recorder::new(path)?),
+            None => None,
+        };
+
+        let service = if trace || recorder.is_some() || self.events.logs_traffic() {
+            if trace {
+                self.tracer.reset_pending(&config.id);
+            }
+            let events = self.events.clone();
+            let tracer = self.tracer.clone();
+            let server_id = config.id.clone();
+            let observer: MessageObserver = Arc::new(move |direction, message| {
+                let message = events.redactor().redact_json(message);
+                events.record_traffic(&server_id, direction, &message);
+                if let Some(recorder) = &recorder {
+                    recorder.record(direction, &message);
+                }
+                if trace {
+                    for entry in tracer.record(&server_id, direction, &message) {
+                        events.emit(McpEvent::Trace(entry));
+                    }
+                }
+            });
+            rmcp::service::serve_client(handler, ObservedTransport::new(transport, observer)).await
+        } else {
+            rmcp::service::serve_client(handler, transport).await
+        };
+        service.map_err(|e| {
+            Error::CommunicationError(format!("Failed to initialize MCP service: {}", e))
+        })
+    }
+
+    /// Forward a server's stderr as leveled log events
+    ///
+    /// Stderr closes when the process exits. If the server is still registered
+    /// at that point it was not stopped on request, so it is removed and
+    /// reported as crashed.
+    ///
+    /// The server's secret values are masked until its output has ended, which
+    /// also covers the lines of a server whose start failed.
+    fn forward_stderr(
+        &self,
+        server_id: String,
+        order: u64,
+        stderr: ChildStderr,
+        mut parser: StderrParser,
+    ) {
+        let events = self.events.clone();
+        let servers = self.servers.clone();
+        let emit = {
+            let events = events.clone();
+            let server_id = server_id.clone();
+            move |entry: ParsedLog| {
+                events.emit(McpEvent::log(&entry.level, &entry.message, Some(&server_id)));
+            }
+        };
+
+        tokio::spawn(async move {
+            let reader = BufReader::new(stderr);
+            let mut lines = reader.lines();
+
+            loop {
+                // A held-back entry is complete once no continuation line follows quickly
+                let line = if parser.has_pending() {
+                    match tokio::time::timeout(STDERR_FLUSH_DELAY, lines.next_line()).await {
+                        Ok(line) => line,
+                        Err(_) => {
+                            parser.flush().into_iter().for_each(&emit);
+                            continue;
+                        }
+                    }
+                } else {
+                    lines.next_line().await
+                };
+
+                match line {
+                    Ok(Some(line)) => parser.push_line(&line).into_iter().for_each(&emit),
+                    _ => break,
+                }
+            }
+            parser.flush().into_iter().for_each(&emit);
+
+            // A restarted server with the same ID has a different order
+            if let Some((_, server)) =
+                servers.remove_if(&server_id, |_, server| server.order == order)
+            {
+                if let Some(service) = server.client.lock().await.take() {
+                    if let Err(e) = service.cancel().await {
+                        debug!("Error cancelling MCP service of crashed server: {}", e);
+                    }
+                }
+                events.emit(McpEvent::server_crashed(
+                    &server_id,
+                    "Server process exited unexpectedly",
+                ));
+                events.emit(McpEvent::error(
+                    &format!("Server '{}' exited unexpectedly", server_id),
+                    Some(&server_id),
+                ));
+            }
+
+            events.redactor().remove_values(&server_id, order);
+        });
+    }
+
+    /// End a process tree the way stdio MCP servers expect to be shut down
+    ///
+    /// The server's input is closed first, then the processes still running are sent
+    /// SIGTERM, and then SIGKILL, each step following only once `grace_period` passed
+    /// without all of them exiting.
+    async fn stop_process_tree(
+        &self,
+        pid: u32,
+        input: Option<&InputCloser>,
+        grace_period: Duration,
+    ) -> Result<()> {
+        // The tree is taken up front; descendants lose their link to the server once it exits
+        let tree = {
+            let mut processes = self.processes.lock().await;
+            processes.refresh_process_list();
+            let root = Pid::from_u32(pid);
+            let mut tree = processes.descendants(root);
+            tree.reverse();
+            tree.push(root);
+            tree
+        };
+
+        if let Some(input) = input {
+            input.close();
+            if self.wait_for_exit(&tree, grace_period).await {
+                return Ok(());
+            }
+        }
+        self.signal_processes(&tree, Signal::Term).await;
+        if self.wait_for_exit(&tree, grace_period).await {
+            return Ok(());
+        }
+        warn!("Server process {} did not exit after SIGTERM; killing it", pid);
+        if self.signal_processes(&tree, Signal::Kill).await.contains(&Pid::from_u32(pid)) {
+            return Err(Error::ProcessStopError(format!("Failed to kill process: {}", pid)));
+        }
+        Ok(())
+    }
+
+    /// Send a signal to the processes still running, returning those it could not be sent to
+    async fn signal_processes(&self, pids: &[Pid], signal: Signal) -> Vec<Pid> {
+        let mut processes = self.processes.lock().await;
+        processes.refresh_process_list();
+        pids.iter()
+            .copied()
+            .filter(|&pid| match processes.process(pid) {
+                Some(process) if process.status() != ProcessStatus::Zombie => {
+                    debug!("Sending {:?} to process: {}", signal, pid);
+                    process.kill_with(signal) != Some(true)
+                }
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Wait until none of the processes is running, or the timeout passes
+    async fn wait_for_exit(&self, pids: &[Pid], timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let running = {
+                let mut processes = self.processes.lock().await;
+                processes.refresh_process_list();
+                pids.iter().any(|&pid| {
+                    processes
+                        .process(pid)
+                        .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+                })
+            };
+            if !running {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+        }
+    }
+
     /// Kill a process and all its descendants
     async fn kill_process_tree(&self, pid: i32) -> Result<()> {
-        let mut system = self.system.lock().await;
-        system.refresh_all();
+        let mut processes = self.processes.lock().await;
+        processes.refresh_process_list();
 
         // Build process tree
-        let mut children = Vec::new();
-        Self::find_child_processes(&system, pid, &mut children);
+        let children = processes.descendants(Pid::from_u32(pid as u32));
 
         // Kill children first (in reverse order to avoid orphaning)
         for &child_pid in children.iter().rev() {
-            let pid_value = child_pid as u32;
-            if let Some(process) = system.process(Pid::from_u32(pid_value)) {
+            if let Some(process) = processes.process(child_pid) {
                 debug!("Killing child process: {}", child_pid);
                 if !process.kill() {
                     warn!("Failed to kill child process: {}", child_pid);
@@ -210,7 +851,7 @@ impl McpManager {
 
         // Kill the main process
         let pid_value = pid as u32;
-        if let Some(process) = system.process(Pid::from_u32(pid_value)) {
+        if let Some(process) = processes.process(Pid::from_u32(pid_value)) {
             debug!("Killing main process: {}", pid);
             if !process.kill() {
                 return Err(Error::ProcessStopError(format!(
@@ -223,55 +864,427 @@ impl McpManager {
         Ok(())
     }
 
-    /// Find all child processes of a given process
-    fn find_child_processes(
-        system: &System,
-        parent_pid: i32,
-        children: &mut Vec<i32>,
-    ) {
-        for process in system.processes().values() {
-            if let Some(ppid) = process.parent() {
-                let ppid_value = ppid.as_u32() as i32;
-                if ppid_value == parent_pid {
-                    let child_pid = process.pid().as_u32() as i32;
-                    children.push(child_pid);
-                    // Recursively find children of this child
-                    Self::find_child_processes(system, child_pid, children);
+    /// Measure the resource usage of a server's process tree
+    ///
+    /// CPU usage is averaged since the server's previous measurement, or over a
+    /// short interval when it was not measured before.
+    pub async fn get_server_stats(&self, server_id: &str) -> Result<ServerStats> {
+        let not_found =
+            || Error::ServerNotFound(format!("Server with ID '{}' not found", server_id));
+        let pid = self
+            .servers
+            .get(server_id)
+            .ok_or_else(not_found)?
+            .pid
+            .ok_or_else(|| {
+                Error::Other(format!("Server '{}' is a replay without a process", server_id))
+            })?;
+        let mut processes = self.processes.lock().await;
+        if !processes.was_measured(pid) {
+            // CPU usage needs two measurements some time apart
+            processes.measure(&[pid]);
+            tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        }
+        processes.measure(&[pid]).remove(&pid).ok_or_else(not_found)
+    }
+
+    /// Emit a `serverStats` event for every server with a process at a fixed interval
+    ///
+    /// Replaces any earlier watch.
+    pub fn watch_stats(self: &Arc<Self>, options: StatsWatchOptions) {
+        let interval = options
+            .interval_ms
+            .map_or(process_stats::DEFAULT_STATS_INTERVAL, |ms| {
+                Duration::from_millis(ms.max(1).into())
+            });
+        let manager = Arc::downgrade(self);
+        let task = tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                for (server_id, stats) in manager.measure_servers().await {
+                    manager.emit_event(McpEvent::server_stats(&server_id, stats));
+                }
+            }
+        });
+        *self.stats_watch.lock().unwrap() = Some(AbortOnDrop::new(task));
+    }
+
+    /// Stop the `serverStats` events started by `watch_stats`
+    pub fn unwatch_stats(&self) {
+        self.stats_watch.lock().unwrap().take();
+    }
+
+    /// Compare a server's usage against its resource limits at their check interval
+    ///
+    /// Soft limits are reported once each time usage climbs to them. The first hard
+    /// limit reached stops or restarts the server, which ends the watch.
+    fn watch_limits(&self, server_id: &str, pid: u32, limits: ResourceLimits) -> AbortOnDrop {
+        let manager = self.this.clone();
+        let server_id = server_id.to_string();
+        let mut tracker = LimitTracker::new(limits);
+        let task = tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(tracker.interval());
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                let Some(stats) = manager.processes.lock().await.measure(&[pid]).remove(&pid)
+                else {
+                    continue;
+                };
+                for breach in tracker.check(&stats, std::time::Instant::now()) {
+                    if breach.level == LimitLevel::Hard {
+                        // Stopping the server aborts this task, so it happens on its own
+                        tokio::spawn(manager.enforce_limit(server_id, breach, tracker));
+                        return;
+                    }
+                    manager.emit_event(McpEvent::warn(
+                        &format!(
+                            "Server '{}' reached its soft {} limit: {}",
+                            server_id,
+                            limit_name(breach.resource),
+                            limit_usage(&breach)
+                        ),
+                        Some(&server_id),
+                    ));
+                    manager.limit_history.entry(server_id.clone()).or_default().last_breach =
+                        Some(breach.clone());
+                    manager.emit_event(McpEvent::resource_limit(&server_id, breach, None));
                 }
             }
+        });
+        AbortOnDrop::new(task)
+    }
+
+    /// Stop or restart a server that reached a hard resource limit
+    ///
+    /// A server that used up its restarts within the restart window is stopped instead.
+    async fn enforce_limit(self: Arc<Self>, server_id: String, breach: LimitBreach, tracker: LimitTracker) {
+        let Some(config) = self.servers.get(&server_id).map(|server| server.config.clone()) else {
+            return;
+        };
+        let now = std::time::Instant::now();
+        let recent_restarts = {
+            let mut history = self.limit_history.entry(server_id.clone()).or_default();
+            history.last_breach = Some(breach.clone());
+            history
+                .restart_times
+                .retain(|&time| now.duration_since(time) < tracker.restart_window());
+            history.restart_times.len()
+        };
+        let mut action = tracker.action();
+        if action == LimitAction::Restart && recent_restarts >= tracker.max_restarts() as usize {
+            self.emit_event(McpEvent::error(
+                &format!(
+                    "Server '{}' reached its limit of {} restarts within {} seconds; stopping it",
+                    server_id,
+                    tracker.max_restarts(),
+                    tracker.restart_window().as_secs()
+                ),
+                Some(&server_id),
+            ));
+            action = LimitAction::Stop;
+        }
+        if action == LimitAction::Stop {
+            // Recorded before the server stops, so it shows once `serverStopped` is emitted
+            self.limit_history.entry(server_id.clone()).or_default().stopped = true;
+        }
+        let verb = match action {
+            LimitAction::Restart => "restarting",
+            LimitAction::Stop => "stopping",
+        };
+        self.emit_event(McpEvent::warn(
+            &format!(
+                "Server '{}' reached its hard {} limit: {}; {} it",
+                server_id,
+                limit_name(breach.resource),
+                limit_usage(&breach),
+                verb
+            ),
+            Some(&server_id),
+        ));
+        self.emit_event(McpEvent::resource_limit(&server_id, breach, Some(action)));
+
+        if let Err(e) = self.stop_server(&server_id, Some(tracker.grace_period())).await {
+            self.emit_event(McpEvent::error(
+                &format!("Failed to stop server after it reached a resource limit: {}", e),
+                Some(&server_id),
+            ));
+            return;
+        }
+        if action == LimitAction::Stop {
+            return;
+        }
+        match self.start(config).await {
+            Ok(()) => {
+                let mut history = self.limit_history.entry(server_id).or_default();
+                history.restarts += 1;
+                history.restart_times.push(now);
+            }
+            Err(e) => {
+                self.emit_event(McpEvent::error(
+                    &format!("Failed to restart server after it reached a resource limit: {}", e),
+                    Some(&server_id),
+                ));
+                self.limit_history.entry(server_id).or_default().stopped = true;
+            }
         }
     }
 
+    /// Measure every server with a process, ordered by server ID
+    async fn measure_servers(&self) -> Vec<(String, ServerStats)> {
+        let mut servers: Vec<(String, u32)> = self
+            .servers
+            .iter()
+            .filter_map(|server| server.pid.map(|pid| (server.key().clone(), pid)))
+            .collect();
+        servers.sort();
+        let roots: Vec<u32> = servers.iter().map(|(_, pid)| *pid).collect();
+        let mut stats = self.processes.lock().await.measure(&roots);
+        servers
+            .into_iter()
+            .filter_map(|(server_id, pid)| Some((server_id, stats.remove(&pid)?)))
+            .collect()
+    }
+
+    /// Get the running servers, in start order, with secret values in their env masked
+    pub fn list_servers(&self) -> Vec<ServerInfo> {
+        let mut servers: Vec<(u64, ServerInfo)> = self
+            .servers
+            .iter()
+            .map(|server| {
+                let mut config = server.config.clone();
+                if let Some(env) = &mut config.env {
+                    for value in env.values_mut() {
+                        *value = secrets::mask_literals(value, |literal| {
+                            self.events.redactor().redact(literal).into_owned()
+                        });
+                    }
+                }
+                let history = self.limit_history.get(server.key());
+                let info = ServerInfo {
+                    server_id: server.key().clone(),
+                    config,
+                    pid: server.pid,
+                    config_hash: server.config_hash.clone(),
+                    tool_count: server.tools.len() as u32,
+                    limit_restarts: history.as_ref().map_or(0, |history| history.restarts),
+                    last_limit_breach: history.and_then(|history| history.last_breach.clone()),
+                };
+                (server.order, info)
+            })
+            .collect();
+        servers.sort_by_key(|(order, _)| *order);
+        servers.into_iter().map(|(_, info)| info).collect()
+    }
+
+    /// Get the resource limits a server reached, also after a hard limit stopped it
+    ///
+    /// Returns `None` when the server reached no limit since it was last stopped by `stop`.
+    pub fn get_limit_status(&self, server_id: &str) -> Option<LimitStatus> {
+        self.limit_history.get(server_id).map(|history| LimitStatus {
+            server_id: server_id.to_string(),
+            restarts: history.restarts,
+            last_breach: history.last_breach.clone(),
+            stopped: history.stopped,
+        })
+    }
+
     /// Get all available tools across all servers
     pub fn get_tools(&self) -> Vec<ToolInfo> {
-        let mut tools = Vec::new();
-        for server in self.servers.iter() {
-            tools.extend(server.tools.clone());
+        self.qualified_tools().tools
+    }
+
+    /// Set how tool names are made unique across servers
+    ///
+    /// Qualified names change with the policy, so every running server gets a
+    /// `toolsUpdated` event with its tools under the new names.
+    pub fn set_namespace_policy(&self, policy: NamespacePolicy) {
+        let previous = std::mem::replace(&mut *self.namespace_policy.write().unwrap(), policy);
+        if previous == policy {
+            return;
         }
-        tools
+
+        let mut servers: Vec<(u64, String)> = self
+            .servers
+            .iter()
+            .map(|server| (server.order, server.key().clone()))
+            .collect();
+        servers.sort();
+        let QualifiedTools { tools, shadowed } = self.qualified_tools();
+        for (_, server_id) in servers {
+            for tool in shadowed.iter().filter(|t| t.server_id == server_id) {
+                self.emit_event(McpEvent::warn(
+                    &format!(
+                        "Tool '{}' is hidden because another server already provides '{}'",
+                        tool.tool_name, tool.qualified_name
+                    ),
+                    Some(&server_id),
+                ));
+            }
+            let server_tools = tools
+                .iter()
+                .filter(|t| t.server_id == server_id)
+                .cloned()
+                .collect();
+            self.emit_event(McpEvent::tools_updated(&server_id, server_tools));
+        }
+    }
+
+    /// Get the current namespacing policy
+    pub fn namespace_policy(&self) -> NamespacePolicy {
+        *self.namespace_policy.read().unwrap()
+    }
+
+    /// Assign qualified names to the tools of all servers in start order
+    fn qualified_tools(&self) -> QualifiedTools {
+        qualified_tools(&self.servers, self.namespace_policy())
+    }
+
+    /// Hook storing a server's tools again after it reported that they changed
+    ///
+    /// `order` tells the server apart from a later one started with the same ID.
+    fn tools_changed_hook(&self, server_id: &str, order: u64, filter: Arc<ToolFilter>) -> ToolsChangedHook {
+        let servers = self.servers.clone();
+        let events = self.events.clone();
+        let namespace_policy = self.namespace_policy.clone();
+        let server_id = server_id.to_string();
+        Box::new(move |tools| {
+            let tool_names: Vec<&str> = tools.iter().map(|tool| &*tool.name).collect();
+            if let Err(e) = filter.check_aliases(&server_id, &tool_names) {
+                events.emit(McpEvent::warn(
+                    &format!("Ignoring changed tools: {}", e),
+                    Some(&server_id),
+                ));
+                return;
+            }
+            let (tools, original_names) = expose_tools(&server_id, &filter, tools);
+
+            // A server that is still starting lists its tools once connected
+            match servers.get_mut(&server_id) {
+                Some(mut server) if server.order == order => {
+                    server.tools = tools;
+                    server.original_names = original_names;
+                }
+                _ => return,
+            }
+
+            let policy = *namespace_policy.read().unwrap();
+            let tools = qualified_tools(&servers, policy)
+                .tools
+                .into_iter()
+                .filter(|t| t.server_id == server_id)
+                .collect();
+            events.emit(McpEvent::tools_updated(&server_id, tools));
+        })
+    }
+
+    /// Get all available tools as definitions for an LLM provider
+    pub fn get_tools_as(&self, format: ToolFormat) -> Vec<ProviderTool> {
+        tool_format::export_tools(&self.get_tools(), format)
+    }
+
+    /// Find the tool behind a name produced by `get_tools_as`
+    pub fn resolve_tool_name(&self, name: &str) -> Option<ToolInfo> {
+        tool_format::resolve_name(&self.get_tools(), name)
+    }
+
+    /// Execute a tool identified by its qualified name
+    pub async fn execute_tool_by_qualified_name(
+        &self,
+        qualified_name: &str,
+        inputs: String,
+    ) -> Result<String> {
+        let tool = self
+            .get_tools()
+            .into_iter()
+            .find(|t| t.qualified_name == qualified_name)
+            .ok_or_else(|| {
+                Error::ToolNotFound(format!("Tool '{}' not found", qualified_name))
+            })?;
+
+        self.execute_tool(ToolExecutionRequest {
+            server_id: tool.server_id,
+            tool_name: tool.tool_name,
+            inputs,
+            ..Default::default()
+        })
+        .await
     }
 
     /// Execute a tool on an MCP server
     pub async fn execute_tool(&self, request: ToolExecutionRequest) -> Result<String> {
-        // Check if server exists
-        let server = self.servers.get(&request.server_id).ok_or_else(|| {
-            Error::ServerNotFound(format!(
-                "Server with ID '{}' not found",
-                request.server_id
-            ))
+        // Look up everything needed up front so no map entry stays locked while waiting
+        let (client, original_name, annotations, timeout_ms) = {
+            // Check if server exists
+            let server = self.servers.get(&request.server_id).ok_or_else(|| {
+                Error::ServerNotFound(format!(
+                    "Server with ID '{}' not found",
+                    request.server_id
+                ))
+            })?;
+
+            // Check if tool exists; hidden tools are never listed, so they cannot be called
+            let original_name = server
+                .original_names
+                .get(&request.tool_name)
+                .cloned()
+                .ok_or_else(|| {
+                    Error::ToolNotFound(format!(
+                        "Tool '{}' not found on server '{}'",
+                        request.tool_name, request.server_id
+                    ))
+                })?;
+            let annotations = server
+                .tools
+                .iter()
+                .find(|t| t.tool_name == request.tool_name)
+                .and_then(|t| t.annotations.clone());
+
+            let timeout_ms = request.timeout_ms.or(server.config.tool_timeout_ms);
+
+            (server.client.clone(), original_name, annotations, timeout_ms)
+        };
+
+        // Parse inputs from JSON string
+        let arguments = parse_arguments(&request.inputs).map_err(|e| {
+            Error::ToolExecutionError(format!("Failed to parse tool inputs: {}", e))
         })?;
 
-        // Check if tool exists
-        let tool = server.value()
-            .tools
-            .iter()
-            .find(|t| t.tool_name == request.tool_name)
-            .ok_or_else(|| {
-                Error::ToolNotFound(format!(
-                    "Tool '{}' not found on server '{}'",
-                    request.tool_name, request.server_id
-                ))
+        // Let interceptors rewrite the call before anyone sees it
+        let mut call = self
+            .interceptors
+            .before_call(ToolCall {
+                server_id: request.server_id.clone(),
+                tool_name: request.tool_name.clone(),
+                arguments,
+            })
+            .await?;
+
+        // Ask for approval, which may replace the arguments
+        let inputs = self
+            .approvals
+            .check(ApprovalRequest {
+                server_id: request.server_id.clone(),
+                tool_name: request.tool_name.clone(),
+                arguments: serde_json::Value::Object(call.arguments.clone()).to_string(),
+                annotations,
+            })
+            .await
+            .inspect_err(|e| {
+                self.emit_event(McpEvent::warn(&e.to_string(), Some(&request.server_id)));
             })?;
+        // After-call hooks see the arguments the tool actually ran with
+        call.arguments = parse_arguments(&inputs).map_err(|e| {
+            Error::ToolExecutionError(format!("Failed to parse approved tool inputs: {}", e))
+        })?;
 
         self.emit_event(McpEvent::info(
             &format!(
@@ -281,29 +1294,59 @@ impl McpManager {
             Some(&request.server_id),
         ));
 
-        // Parse inputs from JSON string
-        let inputs_value: serde_json::Value = serde_json::from_str(&request.inputs).map_err(|e| {
-            Error::ToolExecutionError(format!("Failed to parse tool inputs: {}", e))
-        })?;
-
         // Execute tool
-        let result = server.value()
-            .client
+        let peer = client
             .lock()
             .await
             .as_ref()
-            .unwrap()
-            .call_tool(rmcp::model::CallToolRequestParam {
-                name: request.tool_name.clone().into(),
-                arguments: inputs_value.as_object().cloned(),
-            })
-            .await
-            .map_err(|e| {
-                Error::ToolExecutionError(format!("Failed to execute tool: {}", e))
+            .map(|service| service.peer().clone())
+            .ok_or_else(|| {
+                Error::CommunicationError(format!(
+                    "Server '{}' is not connected",
+                    request.server_id
+                ))
             })?;
+        // The peer attaches a progress token of its own; the caller's replaces it
+        let mut options = PeerRequestOptions::no_options();
+        options.timeout = timeout_ms.map(|ms| Duration::from_millis(u64::from(ms)));
+        if let Some(token) = &request.progress_token {
+            let mut meta = Meta::new();
+            meta.set_progress_token(ProgressToken(NumberOrString::String(token.as_str().into())));
+            options.meta = Some(meta);
+        }
+        let call_request = ClientRequest::CallToolRequest(CallToolRequest::new(
+            CallToolRequestParam {
+                name: original_name.into(),
+                arguments: Some(call.arguments.clone()),
+            },
+        ));
+        let response = match peer.send_request_with_option(call_request, options).await {
+            Ok(handle) => handle.await_response().await,
+            Err(e) => Err(e),
+        };
+        let result = match response.map_err(|e| match e {
+            ServiceError::Timeout { timeout } => Error::ToolExecutionError(format!(
+                "Tool call timed out after {} ms",
+                timeout.as_millis()
+            )),
+            e => Error::ToolExecutionError(format!("Failed to execute tool: {}", e)),
+        })? {
+            ServerResult::CallToolResult(result) => result,
+            _ => {
+                return Err(Error::ToolExecutionError(
+                    "Unexpected response to tool call".to_string(),
+                ))
+            }
+        };
+
+        // Let interceptors post-process the result
+        let result_value = serde_json::to_value(&result).map_err(|e| {
+            Error::ToolExecutionError(format!("Failed to serialize tool result: {}", e))
+        })?;
+        let result_value = self.interceptors.after_call(&call, result_value).await?;
 
         // Convert result to JSON string
-        let result_json = serde_json::to_string(&result).map_err(|e| {
+        let result_json = serde_json::to_string(&result_value).map_err(|e| {
             Error::ToolExecutionError(format!("Failed to serialize tool result: {}", e))
         })?;
 
@@ -315,13 +1358,297 @@ impl McpManager {
             Some(&request.server_id),
         ));
         
-        return Ok(result_json);
+        Ok(result_json)
+    }
+
+    /// Set the minimum level of MCP log messages a server sends
+    pub async fn set_log_level(&self, server_id: &str, level: &str) -> Result<()> {
+        let level = client_handler::parse_log_level(level)
+            .ok_or_else(|| Error::ConfigError(format!("Invalid log level '{}'", level)))?;
+
+        let peer = self.peer(server_id).await?;
+
+        let supports_logging = peer
+            .peer_info()
+            .map(|info| info.capabilities.logging.is_some())
+            .unwrap_or(false);
+        if !supports_logging {
+            return Err(Error::CommunicationError(format!(
+                "Server '{}' does not support MCP logging",
+                server_id
+            )));
+        }
+
+        peer.set_level(SetLevelRequestParam { level })
+            .await
+            .map_err(|e| Error::CommunicationError(format!("Failed to set log level: {}", e)))
+    }
+
+    /// List the resources a server offers; empty when it does not support resources
+    pub async fn list_resources(&self, server_id: &str) -> Result<Vec<ResourceInfo>> {
+        let peer = self.peer(server_id).await?;
+        let supports_resources = peer
+            .peer_info()
+            .map(|info| info.capabilities.resources.is_some())
+            .unwrap_or(false);
+        if !supports_resources {
+            return Ok(Vec::new());
+        }
+
+        let resources = peer.list_all_resources().await.map_err(|e| {
+            Error::CommunicationError(format!("Failed to list resources: {}", e))
+        })?;
+        Ok(resources
+            .into_iter()
+            .map(|resource| ResourceInfo {
+                server_id: server_id.to_string(),
+                uri: resource.raw.uri,
+                name: resource.raw.name,
+                description: resource.raw.description,
+                mime_type: resource.raw.mime_type,
+            })
+            .collect())
+    }
+
+    /// List the prompts a server offers; empty when it does not support prompts
+    pub async fn list_prompts(&self, server_id: &str) -> Result<Vec<PromptInfo>> {
+        let peer = self.peer(server_id).await?;
+        let supports_prompts = peer
+            .peer_info()
+            .map(|info| info.capabilities.prompts.is_some())
+            .unwrap_or(false);
+        if !supports_prompts {
+            return Ok(Vec::new());
+        }
+
+        let prompts = peer.list_all_prompts().await.map_err(|e| {
+            Error::CommunicationError(format!("Failed to list prompts: {}", e))
+        })?;
+        Ok(prompts
+            .into_iter()
+            .map(|prompt| PromptInfo {
+                server_id: server_id.to_string(),
+                name: prompt.name,
+                description: prompt.description,
+                arguments: prompt
+                    .arguments
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|argument| PromptArgumentInfo {
+                        name: argument.name,
+                        description: argument.description,
+                        required: argument.required.unwrap_or(false),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Append an interceptor to the tool call chain and return its ID
+    pub fn add_interceptor(&self, interceptor: Arc<dyn ToolInterceptor>) -> u32 {
+        self.interceptors.add(interceptor)
+    }
+
+    /// Remove an interceptor from the tool call chain
+    pub fn remove_interceptor(&self, id: u32) -> bool {
+        self.interceptors.remove(id)
+    }
+
+    /// Set the provider resolving `${secret:key}` references in server env values
+    pub fn set_secret_provider(&self, provider: Option<Arc<dyn SecretProvider>>) {
+        *self.secret_provider.write().unwrap() = provider;
+    }
+
+    /// Register the handler asked before tools whose policy is `AlwaysAsk` run
+    pub fn set_approval_handler(&self, handler: Option<ApprovalHandler>) {
+        self.approvals.set_handler(handler);
+    }
+
+    /// Set whether calls to a tool need approval
+    pub fn set_tool_approval_policy(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        policy: ApprovalPolicy,
+    ) {
+        self.approvals.set_policy(server_id, tool_name, policy);
+    }
+
+    /// Remove a tool's approval policy so the default policy applies again
+    pub fn clear_tool_approval_policy(&self, server_id: &str, tool_name: &str) {
+        self.approvals.clear_policy(server_id, tool_name);
+    }
+
+    /// Get the approval policy that applies to a tool
+    pub fn tool_approval_policy(&self, server_id: &str, tool_name: &str) -> ApprovalPolicy {
+        self.approvals.policy(server_id, tool_name)
+    }
+
+    /// Set the approval policy for tools without a policy of their own
+    ///
+    /// Until it is set, such tools are asked about while an approval handler is
+    /// registered and run without asking otherwise.
+    pub fn set_default_approval_policy(&self, policy: ApprovalPolicy) {
+        self.approvals.set_default_policy(policy);
+    }
+
+    /// Get the last lines of a server's log file
+    pub fn get_log_tail(&self, server_id: &str, lines: usize) -> Result<Vec<String>> {
+        let log_files = self.events.log_files().ok_or_else(|| {
+            Error::ConfigError("No log directory is configured".to_string())
+        })?;
+        log_files.tail(server_id, lines)
+    }
+
+    /// Get recently emitted events matching a query, oldest first
+    pub fn get_recent_events(&self, query: &EventQuery) -> Vec<McpEvent> {
+        self.events.recent(query)
+    }
+
+    /// Replace the event callback, optionally replaying buffered events newer than a sequence number
+    pub fn set_event_callback(
+        &self,
+        callback: Arc<dyn EventSink>,
+        replay_since: Option<i64>,
+    ) {
+        self.events.set_callback(callback, replay_since);
+    }
+
+    /// Get the captured JSON-RPC traffic of a traced server, oldest first
+    pub fn get_trace(&self, server_id: &str, query: &TraceQuery) -> Vec<TraceEntry> {
+        self.tracer.query(server_id, query)
+    }
+
+    /// Drop the captured JSON-RPC traffic of a server
+    pub fn clear_trace(&self, server_id: &str) {
+        self.tracer.clear(server_id);
+    }
+
+    /// Write the captured JSON-RPC traffic of a server to a file
+    pub fn export_trace(&self, server_id: &str, path: &str, format: TraceFormat) -> Result<()> {
+        let export = self.tracer.export(server_id, format);
+        let content = serde_json::to_string_pretty(&export)
+            .map_err(|e| Error::Other(format!("Failed to serialize trace: {}", e)))?;
+        std::fs::write(path, content)
+            .map_err(|e| Error::Other(format!("Failed to write trace to '{}': {}", path, e)))
+    }
+
+    /// Register a filtered event callback and return its subscription ID
+    ///
+    /// The callback must not call back into the manager; see [`EventSink`].
+    pub fn subscribe(
+        &self,
+        filter: EventFilter,
+        callback: Arc<dyn EventSink>,
+        replay_since: Option<i64>,
+    ) -> u32 {
+        self.events.subscribe(filter, callback, replay_since)
+    }
+
+    /// Remove an event subscription, returning whether it was registered
+    pub fn unsubscribe(&self, id: u32) -> bool {
+        self.events.unsubscribe(id)
+    }
+
+    /// Get the connection to a running server
+    async fn peer(&self, server_id: &str) -> Result<Peer<RoleClient>> {
+        let client = self
+            .servers
+            .get(server_id)
+            .map(|server| server.client.clone())
+            .ok_or_else(|| {
+                Error::ServerNotFound(format!("Server with ID '{}' not found", server_id))
+            })?;
+        let peer = client
+            .lock()
+            .await
+            .as_ref()
+            .map(|service| service.peer().clone())
+            .ok_or_else(|| {
+                Error::CommunicationError(format!("Server '{}' is not connected", server_id))
+            })?;
+        Ok(peer)
     }
 
     /// Emit an event to TypeScript
     fn emit_event(&self, event: McpEvent) {
-        self.event_callback
-            .call(Ok(event), napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking);
+        self.events.emit(event);
+    }
+}
+
+/// Assign qualified names to the tools of all servers in start order
+fn qualified_tools(servers: &DashMap<String, Server>, policy: NamespacePolicy) -> QualifiedTools {
+    let mut servers: Vec<_> = servers.iter().collect();
+    servers.sort_by_key(|server| server.order);
+    namespace::qualify(
+        servers
+            .iter()
+            .map(|server| (&server.config, server.tools.as_slice())),
+        policy,
+    )
+}
+
+/// Convert a server's tools to our format, hiding and renaming them as configured
+///
+/// Also returns the map of exposed tool name to original name.
+fn expose_tools(
+    server_id: &str,
+    filter: &ToolFilter,
+    tools: Vec<Tool>,
+) -> (Vec<ToolInfo>, HashMap<String, String>) {
+    let mut original_names = HashMap::new();
+    let tools = tools
+        .into_iter()
+        .filter(|tool| filter.allows(&tool.name))
+        .map(|tool| {
+            let tool_name = filter.exposed_name(&tool.name);
+            original_names.insert(tool_name.clone(), tool.name.to_string());
+            ToolInfo {
+                server_id: server_id.to_string(),
+                tool_name: tool_name.clone(),
+                qualified_name: tool_name,
+                description: tool.description.unwrap_or_default().to_string(),
+                parameters: serde_json::to_string(&tool.input_schema).unwrap_or_default(),
+                annotations: tool.annotations.map(Into::into),
+            }
+        })
+        .collect();
+    (tools, original_names)
+}
+
+/// Hash of a server configuration that is stable across builds and platforms
+///
+/// The configuration is hashed through its JSON value, whose objects have sorted
+/// keys, so equal configurations hash alike whatever the order of their maps.
+fn config_hash(config: &ServerConfig) -> String {
+    let canonical = serde_json::to_value(config)
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    format!("{:016x}", fnv1a_64(canonical.as_bytes()))
+}
+
+/// Name of a limited resource for log messages
+fn limit_name(resource: LimitedResource) -> &'static str {
+    match resource {
+        LimitedResource::Memory => "memory",
+        LimitedResource::Cpu => "CPU",
     }
 }
 
+/// Usage against the limit reached, for log messages
+fn limit_usage(breach: &LimitBreach) -> String {
+    match breach.resource {
+        LimitedResource::Memory => format!("{:.1} MB of {} MB", breach.value, breach.limit),
+        LimitedResource::Cpu => format!("{:.1}% of {}%", breach.value, breach.limit),
+    }
+}
+
+/// Parse tool arguments, which must be a JSON object
+fn parse_arguments(
+    json: &str,
+) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    match serde_json::from_str(json).map_err(|e| e.to_string())? {
+        serde_json::Value::Object(arguments) => Ok(arguments),
+        _ => Err("arguments must be a JSON object".to_string()),
+    }
+}
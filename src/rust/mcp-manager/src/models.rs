@@ -1,36 +1,498 @@
-use napi::bindgen_prelude::*;
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{FromNapiValue, ToNapiValue, TypeName, ValueType};
+#[cfg(feature = "napi")]
+use napi::{JsObject, sys};
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Options for creating an MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagerOptions {
+    /// Directory for per-server log files; nothing is written to disk when omitted
+    pub log_dir: Option<String>,
+    /// Size in bytes at which a server's log file is rotated (default 5 MiB)
+    pub max_log_file_size: Option<u32>,
+    /// Number of rotated log files kept per server (default 3)
+    pub max_log_files: Option<u32>,
+    /// Whether JSON-RPC messages are written to the log files as well
+    pub log_traffic: Option<bool>,
+    /// Number of recent events kept across all servers (default 1000)
+    pub event_buffer_size: Option<u32>,
+    /// Number of recent events kept for each server (default 200)
+    pub server_event_buffer_size: Option<u32>,
+    /// Number of JSON-RPC messages kept for each traced server (default 1000)
+    pub trace_buffer_size: Option<u32>,
+    /// Regular expressions of secrets masked in events, traces and log files, on top of the built-in ones
+    ///
+    /// A `secret` capture group limits the mask to that part of the match.
+    pub redaction_patterns: Option<Vec<String>>,
+    /// Whether the built-in patterns for bearer tokens, cloud and API keys are applied (default true)
+    pub default_redaction_patterns: Option<bool>,
+}
 
 /// Configuration for starting an MCP server
-#[napi(object)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ServerConfig {
     /// Unique identifier for the server
     pub id: String,
     /// Command to execute (path to the server executable)
     pub command: String,
     /// Arguments to pass to the command
+    #[serde(default)]
     pub args: Vec<String>,
+    /// Environment variables set for the server process, on top of the inherited ones
+    ///
+    /// Values may contain `${env:NAME}`, `${file:/path}` and `${secret:key}` references,
+    /// resolved when the process is spawned and masked wherever the manager reports text.
+    /// Values of variables whose names suggest a secret, such as `API_KEY`, are masked too.
+    pub env: Option<HashMap<String, String>>,
+    /// Minimum level of MCP log messages requested from the server once it started
+    pub log_level: Option<String>,
+    /// Regular expression with a `level` capture group that extracts the log level from stderr lines
+    pub log_level_pattern: Option<String>,
+    /// Prefix for this server's tool names under the `customPrefix` namespacing policy
+    pub tool_prefix: Option<String>,
+    /// Glob patterns of tools to expose; all tools are exposed when omitted
+    pub include_tools: Option<Vec<String>>,
+    /// Glob patterns of tools to hide, applied after `includeTools`
+    pub exclude_tools: Option<Vec<String>>,
+    /// Map of original tool name to the name it is exposed as
+    pub aliases: Option<HashMap<String, String>>,
+    /// Whether every JSON-RPC message exchanged with the server is traced
+    pub trace: Option<bool>,
+    /// File the JSON-RPC exchange with the server is recorded to (JSON Lines)
+    pub record: Option<String>,
+    /// Recording to play back instead of starting `command`
+    pub replay: Option<ReplayConfig>,
+    /// Milliseconds to wait for tool results; calls wait indefinitely when omitted
+    pub tool_timeout_ms: Option<u32>,
+    /// Restrictions applied to the server process (Linux only)
+    pub sandbox: Option<SandboxConfig>,
+    /// Memory and CPU usage at which the server is warned about, restarted or stopped
+    pub limits: Option<ResourceLimits>,
+}
+
+/// Memory and CPU usage limits of a server, measured over its whole process tree
+///
+/// Reaching a soft limit emits a `resourceLimit` warning; reaching a hard limit
+/// also restarts or stops the server.
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimits {
+    /// Resident memory in megabytes at which a warning is emitted
+    pub soft_memory_mb: Option<u32>,
+    /// Resident memory in megabytes at which the hard limit action is taken
+    pub hard_memory_mb: Option<u32>,
+    /// CPU usage in percent of one core at which, once sustained, a warning is emitted
+    pub soft_cpu_percent: Option<u32>,
+    /// CPU usage in percent of one core at which, once sustained, the hard limit action is taken
+    pub hard_cpu_percent: Option<u32>,
+    /// Seconds CPU usage must stay at or above a CPU limit to reach it (default 60)
+    pub cpu_window_secs: Option<u32>,
+    /// Milliseconds between measurements (default 5000)
+    pub check_interval_ms: Option<u32>,
+    /// What happens when a hard limit is reached (default `restart`)
+    pub action: Option<LimitAction>,
+    /// Milliseconds a server stopped for reaching a hard limit gets to exit once its input is
+    /// closed, and again after SIGTERM, before it is killed (default 2000)
+    pub grace_period_ms: Option<u32>,
+    /// Restarts for reaching a hard limit allowed within `restartWindowSecs`; once used up the
+    /// server is stopped instead (default 3)
+    pub max_restarts: Option<u32>,
+    /// Seconds over which restarts are counted against `maxRestarts` (default 300)
+    pub restart_window_secs: Option<u32>,
+}
+
+/// What happens to a server that reaches a hard resource limit
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitAction {
+    /// Stop the server and start it again with the same configuration
+    Restart,
+    /// Stop the server
+    Stop,
+}
+
+/// Resource a limit applies to
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitedResource {
+    /// Resident memory, in megabytes
+    Memory,
+    /// Sustained CPU usage, in percent of one core
+    Cpu,
+}
+
+/// Which of a resource's limits was reached
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitLevel {
+    /// Only a warning is emitted
+    Soft,
+    /// The server is restarted or stopped
+    Hard,
+}
+
+/// A resource limit a server reached
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitBreach {
+    /// Resource that reached its limit
+    pub resource: LimitedResource,
+    /// Whether the soft or the hard limit was reached
+    pub level: LimitLevel,
+    /// Measured usage, in megabytes or percent of one core
+    pub value: f64,
+    /// Limit that was reached, in the same unit
+    pub limit: f64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+/// Restrictions applied to a server process before it runs
+///
+/// Only supported on Linux; starting a sandboxed server elsewhere fails.
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxConfig {
+    /// Maximum size of the address space in megabytes (`RLIMIT_AS`)
+    pub max_memory_mb: Option<u32>,
+    /// Maximum number of open file descriptors (`RLIMIT_NOFILE`)
+    pub max_open_files: Option<u32>,
+    /// Seconds of CPU time after which the process is killed (`RLIMIT_CPU`)
+    pub max_cpu_seconds: Option<u32>,
+    /// Maximum number of processes of the user the server runs as (`RLIMIT_NPROC`)
+    pub max_processes: Option<u32>,
+    /// Whether the process may gain privileges through setuid programs (default false)
+    ///
+    /// Ignored when `readPaths` or `writePaths` is set, as Landlock requires `no_new_privs`.
+    pub allow_new_privileges: Option<bool>,
+    /// Paths the server may read and execute below; restricts the filesystem with Landlock
+    ///
+    /// The command itself and the system directories needed to run programs are always readable.
+    pub read_paths: Option<Vec<String>>,
+    /// Paths the server may read and write below; restricts the filesystem with Landlock
+    pub write_paths: Option<Vec<String>>,
+    /// Network access of the server (default `host`)
+    pub network: Option<SandboxNetwork>,
+}
+
+/// Network access of a sandboxed server
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SandboxNetwork {
+    /// Use the network of the host
+    Host,
+    /// Run in a new user and network namespace without any interface but a downed loopback
+    ///
+    /// When namespaces are unavailable the server runs with host networking and a warning is emitted.
+    None,
+}
+
+/// A running server as reported by `listServers`
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfo {
+    /// ID of the server
+    pub server_id: String,
+    /// Configuration the server was started with; env values still hold their secret references
+    pub config: ServerConfig,
+    /// PID of the server process, unless a recording is played back
+    pub pid: Option<u32>,
+    /// Hash of the configuration, as reported by `apply`
+    pub config_hash: String,
+    /// Number of tools the server exposes
+    pub tool_count: u32,
+    /// Number of times the server was restarted for reaching a hard resource limit
+    pub limit_restarts: u32,
+    /// Most recent resource limit the server reached, kept across restarts
+    pub last_limit_breach: Option<LimitBreach>,
+}
+
+/// Resource limits a server reached, as reported by `getLimitStatus`
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitStatus {
+    /// ID of the server
+    pub server_id: String,
+    /// Number of times the server was restarted for reaching a hard resource limit
+    pub restarts: u32,
+    /// Most recent resource limit the server reached
+    pub last_breach: Option<LimitBreach>,
+    /// Whether a hard limit stopped the server, which was not started again since
+    pub stopped: bool,
+}
+
+/// Resource usage of a server, summed over its process and all its descendants
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    /// PID of the server process
+    pub pid: u32,
+    /// CPU usage in percent of one core, averaged since the previous measurement
+    pub cpu_percent: f64,
+    /// Resident memory in bytes
+    pub memory_bytes: f64,
+    /// Virtual memory in bytes
+    pub virtual_memory_bytes: f64,
+    /// Number of threads
+    pub thread_count: u32,
+    /// Number of open file descriptors, unless the platform does not report them
+    pub open_files: Option<u32>,
+    /// Number of descendant processes
+    pub child_count: u32,
+}
+
+/// Playback of a recorded session in place of a real server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayConfig {
+    /// Recording file written through `record`
+    pub path: String,
+    /// How requests are matched against recorded ones (default `exact`)
+    pub matcher: Option<ReplayMatcher>,
+    /// JSON pointers into request params that are ignored when matching, e.g. `/arguments/timestamp`
+    pub ignore_params: Option<Vec<String>>,
+}
+
+/// How replayed requests are matched against recorded ones
+///
+/// `initialize` and `ping` always match by method alone.
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplayMatcher {
+    /// Same method and equal params
+    Exact,
+    /// Same method, with every recorded param present and equal
+    Subset,
+    /// Same method, whatever the params
+    Method,
 }
 
 /// Information about a tool provided by an MCP server
-#[napi(object)]
+#[cfg_attr(feature = "napi", napi(object))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolInfo {
     /// ID of the server that provides this tool
     pub server_id: String,
     /// Name of the tool
     pub tool_name: String,
+    /// Name of the tool, unique across all servers, according to the namespacing policy
+    pub qualified_name: String,
     /// Description of the tool
     pub description: String,
     /// Parameters accepted by the tool (as a JSON string)
     pub parameters: String,
+    /// Hints the server gives about the tool's behaviour
+    pub annotations: Option<ToolAnnotations>,
 }
 
-/// Request to execute a tool on an MCP server
-#[napi(object)]
+/// Hints about a tool's behaviour, as reported by its server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Human-readable title of the tool
+    pub title: Option<String>,
+    /// Whether the tool does not modify its environment
+    pub read_only_hint: Option<bool>,
+    /// Whether the tool may perform destructive updates
+    pub destructive_hint: Option<bool>,
+    /// Whether repeated calls with the same arguments have no additional effect
+    pub idempotent_hint: Option<bool>,
+    /// Whether the tool interacts with external entities
+    pub open_world_hint: Option<bool>,
+}
+
+impl From<rmcp::model::ToolAnnotations> for ToolAnnotations {
+    fn from(annotations: rmcp::model::ToolAnnotations) -> Self {
+        Self {
+            title: annotations.title,
+            read_only_hint: annotations.read_only_hint,
+            destructive_hint: annotations.destructive_hint,
+            idempotent_hint: annotations.idempotent_hint,
+            open_world_hint: annotations.open_world_hint,
+        }
+    }
+}
+
+/// A resource offered by an MCP server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceInfo {
+    /// ID of the server that provides this resource
+    pub server_id: String,
+    /// URI identifying the resource
+    pub uri: String,
+    /// Name of the resource
+    pub name: String,
+    /// Description of the resource
+    pub description: Option<String>,
+    /// MIME type of the resource content
+    pub mime_type: Option<String>,
+}
+
+/// A prompt template offered by an MCP server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptInfo {
+    /// ID of the server that provides this prompt
+    pub server_id: String,
+    /// Name of the prompt
+    pub name: String,
+    /// Description of the prompt
+    pub description: Option<String>,
+    /// Arguments the prompt accepts
+    pub arguments: Vec<PromptArgumentInfo>,
+}
+
+/// An argument of a prompt template
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptArgumentInfo {
+    /// Name of the argument
+    pub name: String,
+    /// Description of the argument
+    pub description: Option<String>,
+    /// Whether the argument must be given
+    pub required: bool,
+}
+
+/// How tool names are made unique across servers
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NamespacePolicy {
+    /// Prefix every tool with the ID of its server (`serverId__toolName`)
+    ServerId,
+    /// Prefix every tool with its server's `toolPrefix`, falling back to the server ID
+    CustomPrefix,
+    /// Keep tool names as-is; on collision the server started first wins
+    FirstWins,
+}
+
+/// LLM provider formats that tools can be exported as
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolFormat {
+    /// OpenAI chat completions `tools` entries
+    OpenAI,
+    /// Anthropic messages `tools` entries
+    Anthropic,
+    /// Gemini `functionDeclarations` entries
+    Gemini,
+}
+
+/// Tool definition ready to be sent to an LLM provider
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTool {
+    /// Provider-safe name of the tool, unique across all servers
+    pub name: String,
+    /// ID of the server that provides this tool
+    pub server_id: String,
+    /// Name of the tool on its server
+    pub tool_name: String,
+    /// Tool definition in the provider's format (as a JSON string)
+    pub definition: String,
+}
+
+/// Options for `apply`
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyOptions {
+    /// Maximum number of servers started or stopped at the same time (default 4)
+    pub concurrency: Option<u32>,
+}
+
+/// Options for `watchConfig`
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWatchOptions {
+    /// Milliseconds the file must stay unchanged before it is reloaded (default 300)
+    pub debounce_ms: Option<u32>,
+}
+
+/// Options for `watchStats`
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsWatchOptions {
+    /// Milliseconds between `serverStats` events (default 5000)
+    pub interval_ms: Option<u32>,
+}
+
+/// What `apply` did with a server
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplyAction {
+    /// The server was not running and was started
+    Started,
+    /// The server is no longer wanted and was stopped
+    Stopped,
+    /// The server's configuration changed, so it was stopped and started again
+    Restarted,
+    /// The server already runs with the same configuration
+    Unchanged,
+    /// Starting or stopping the server failed
+    Failed,
+}
+
+/// Outcome of `apply` for one server
+#[cfg_attr(feature = "napi", napi(object))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyResult {
+    /// ID of the server
+    pub server_id: String,
+    /// What was done with the server
+    pub action: ApplyAction,
+    /// Hash of the configuration the server now runs with, if it runs
+    pub config_hash: Option<String>,
+    /// Why the server could not be started or stopped
+    pub error: Option<String>,
+}
+
+/// Request to execute a tool on an MCP server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolExecutionRequest {
     /// ID of the server to execute the tool on
     pub server_id: String,
@@ -38,21 +500,205 @@ pub struct ToolExecutionRequest {
     pub tool_name: String,
     /// Input parameters for the tool (as a JSON string)
     pub inputs: String,
+    /// Token the server's progress events for this call will carry
+    pub progress_token: Option<String>,
+    /// Milliseconds to wait for the result, overriding the server's `toolTimeoutMs`
+    pub timeout_ms: Option<u32>,
+}
+
+/// Whether calls to a tool need approval
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalPolicy {
+    /// Run the tool without asking
+    AlwaysAllow,
+    /// Ask the approval handler before every call
+    AlwaysAsk,
+    /// Refuse every call
+    Never,
+}
+
+/// Tool call awaiting approval
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    /// ID of the server the tool runs on
+    pub server_id: String,
+    /// Name of the tool to execute
+    pub tool_name: String,
+    /// Arguments the tool will be called with (as a JSON string)
+    pub arguments: String,
+    /// Hints the server gives about the tool's behaviour
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Answer of the approval handler to an approval request
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalDecision {
+    /// Whether the call may proceed
+    pub approved: bool,
+    /// Replacement arguments for the call (as a JSON string)
+    pub arguments: Option<String>,
+    /// Reason given for denying the call
+    pub reason: Option<String>,
+}
+
+/// Tool call passed to JavaScript interceptors
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterceptedCall {
+    /// ID of the server the tool runs on
+    pub server_id: String,
+    /// Name of the tool to execute
+    pub tool_name: String,
+    /// Arguments the tool will be called with (as a JSON string)
+    pub arguments: String,
 }
 
-/// Event emitted by the MCP manager
-#[napi(object)]
+/// Tool call result passed to JavaScript interceptors
+#[cfg_attr(feature = "napi", napi(object))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct McpEvent {
-    /// Type of event
-    pub event_type: String,
-    /// Event payload (as a JSON string)
-    pub payload: String,
+#[serde(rename_all = "camelCase")]
+pub struct InterceptedResult {
+    /// ID of the server the tool ran on
+    pub server_id: String,
+    /// Name of the tool that was executed
+    pub tool_name: String,
+    /// Arguments the tool was called with (as a JSON string)
+    pub arguments: String,
+    /// Result of the call (as a JSON string)
+    pub result: String,
+}
+
+/// Declare `McpEvent` along with everything that follows its list of variants
+///
+/// Each variant's event type feeds serde, `event_type` and the TypeScript union
+/// alike, so a new variant cannot be missing from any of them.
+macro_rules! mcp_events {
+    ($($(#[$meta:meta])* $variant:ident($payload:ident) = $event_type:literal,)*) => {
+        /// Event emitted by the MCP manager
+        ///
+        /// Reaches TypeScript as the variant's object with an `eventType` discriminant.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(tag = "eventType")]
+        pub enum McpEvent {
+            $(
+                $(#[$meta])*
+                #[serde(rename = $event_type)]
+                $variant($payload),
+            )*
+        }
+
+        /// Event type and payload interface of each event, as declared in TypeScript
+        const EVENT_TYPE_DEFS: &[(&str, &str)] = &[$(($event_type, stringify!($payload)),)*];
+
+        impl McpEvent {
+            /// Discriminant of the event as seen by TypeScript
+            pub fn event_type(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $event_type,)*
+                }
+            }
+
+            /// Position of the event among all events of its manager; 0 until it is emitted
+            pub fn sequence(&self) -> i64 {
+                match self {
+                    $(Self::$variant(event) => event.sequence,)*
+                }
+            }
+
+            /// Number the event; done by the emitter when the event is emitted
+            pub fn set_sequence(&mut self, sequence: i64) {
+                match self {
+                    $(Self::$variant(event) => event.sequence = sequence,)*
+                }
+            }
+        }
+
+        #[cfg(feature = "napi")]
+        impl ToNapiValue for McpEvent {
+            /// Convert the variant's object and tag it with its `eventType`
+            unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+                let event_type = val.event_type();
+                let value = match val {
+                    $(Self::$variant(event) => $payload::to_napi_value(env, event)?,)*
+                };
+                let mut object = JsObject::from_napi_value(env, value)?;
+                object.set_named_property("eventType", event_type)?;
+                Ok(value)
+            }
+        }
+    };
+}
+
+mcp_events! {
+    /// Log entry of the manager or a server
+    Log(LogEvent) = "log",
+    /// A server finished starting and its tools are available
+    ServerStarted(ServerStartedEvent) = "serverStarted",
+    /// A server was stopped on request
+    ServerStopped(ServerStoppedEvent) = "serverStopped",
+    /// A server exited without being asked to
+    ServerCrashed(ServerCrashedEvent) = "serverCrashed",
+    /// The tools of a server changed
+    ToolsUpdated(ToolsUpdatedEvent) = "toolsUpdated",
+    /// A server reported progress of a tool call
+    Progress(ProgressEvent) = "progress",
+    /// A JSON-RPC message was exchanged with a traced server
+    Trace(TraceEntry) = "trace",
+    /// A watched config file changed and was applied or rejected
+    ConfigReloaded(ConfigReloadedEvent) = "configReloaded",
+    /// Periodic resource usage of a server
+    ServerStats(ServerStatsEvent) = "serverStats",
+    /// A server reached a soft or hard resource limit
+    ResourceLimit(ResourceLimitEvent) = "resourceLimit",
+}
+
+/// Discriminant of an event, as returned by `McpEvent::event_type`
+///
+/// Declared in TypeScript as the union of all event types.
+pub type McpEventType = String;
+
+/// Filter for querying recently emitted events
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventQuery {
+    /// Only events of this server
+    pub server_id: Option<String>,
+    /// Only events of these types
+    pub event_types: Option<Vec<McpEventType>>,
+    /// Only log events at or above this level; other events are not affected
+    pub min_level: Option<String>,
+    /// Only events with a sequence number greater than this
+    pub since_sequence: Option<i64>,
+    /// Return at most this many of the newest matching events
+    pub limit: Option<u32>,
+}
+
+/// Selects the events delivered to a subscriber
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    /// Only events of these types
+    pub event_types: Option<Vec<McpEventType>>,
+    /// Only events of these servers
+    pub server_ids: Option<Vec<String>>,
+    /// Only log events at or above this level; other events are not affected
+    pub min_level: Option<String>,
 }
 
 /// Log event emitted by the MCP manager
-#[napi(object)]
+#[cfg_attr(feature = "napi", napi(object))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LogEvent {
     /// Log level
     pub level: String,
@@ -60,30 +706,249 @@ pub struct LogEvent {
     pub message: String,
     /// Server ID (if applicable)
     pub server_id: Option<String>,
+    /// Name of the logger that produced the entry (for MCP log messages)
+    pub logger: Option<String>,
+    /// Structured data of the entry (for MCP log messages, as a JSON string)
+    pub data: Option<String>,
+    /// Time the entry was logged, in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Server started event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStartedEvent {
+    /// ID of the server
+    pub server_id: String,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Server stopped event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStoppedEvent {
+    /// ID of the server
+    pub server_id: String,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Server crashed event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCrashedEvent {
+    /// ID of the server
+    pub server_id: String,
+    /// What is known about why the server went away
+    pub reason: String,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
 }
 
 /// Tools updated event emitted by the MCP manager
-#[napi(object)]
+#[cfg_attr(feature = "napi", napi(object))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolsUpdatedEvent {
+    /// ID of the server whose tools changed
+    pub server_id: String,
     /// List of available tools
     pub tools: Vec<ToolInfo>,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Progress event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    /// ID of the server reporting progress
+    pub server_id: String,
+    /// Progress token of the tool call, as given in the execution request
+    pub progress_token: String,
+    /// Progress so far
+    pub progress: u32,
+    /// Total progress required, if known
+    pub total: Option<u32>,
+    /// Description of the current progress
+    pub message: Option<String>,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Config reloaded event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadedEvent {
+    /// Path of the config file
+    pub path: String,
+    /// Servers that were started, stopped, restarted or failed; unchanged ones are left out
+    pub changes: Vec<ApplyResult>,
+    /// Why the file was rejected; servers are left untouched when set
+    pub error: Option<String>,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Server stats event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatsEvent {
+    /// ID of the server
+    pub server_id: String,
+    /// Resource usage of the server's process tree
+    pub stats: ServerStats,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Resource limit event emitted by the MCP manager
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimitEvent {
+    /// ID of the server
+    pub server_id: String,
+    /// Limit that was reached
+    pub breach: LimitBreach,
+    /// What is done about it; only set for hard limits
+    pub action: Option<LimitAction>,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Direction of a JSON-RPC message relative to the manager
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    /// Sent by the manager to the server
+    Outgoing,
+    /// Received by the manager from the server
+    Incoming,
 }
 
-/// Helper functions to create events
+impl Direction {
+    /// Arrow used when writing traffic to logs
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Direction::Outgoing => "->",
+            Direction::Incoming => "<-",
+        }
+    }
+}
+
+/// Kind of a JSON-RPC message
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageKind {
+    /// Request expecting a response
+    Request,
+    /// Successful response to a request
+    Response,
+    /// Error response to a request
+    Error,
+    /// Notification without a response
+    Notification,
+}
+
+/// JSON-RPC message captured while tracing a server
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEntry {
+    /// ID of the server the message was exchanged with
+    pub server_id: String,
+    /// Whether the message was sent or received
+    pub direction: Direction,
+    /// Kind of the message
+    pub kind: MessageKind,
+    /// Method of the request or notification, or of the request a response answers
+    pub method: Option<String>,
+    /// JSON-RPC ID of the request or response (as a JSON string)
+    pub id: Option<String>,
+    /// The message itself (as a JSON string)
+    pub message: String,
+    /// Time the message passed the transport, in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// For responses, milliseconds since the matching request passed the transport
+    pub latency_ms: Option<f64>,
+    /// Position of the event among all events of its manager, increasing monotonically
+    pub sequence: i64,
+}
+
+/// Filter for querying a server's trace
+#[cfg_attr(feature = "napi", napi(object))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceQuery {
+    /// Only messages of these methods
+    pub methods: Option<Vec<String>>,
+    /// Only messages with a sequence number greater than this
+    pub since_sequence: Option<i64>,
+    /// Return at most this many of the newest matching messages
+    pub limit: Option<u32>,
+}
+
+/// File format for exported traces
+#[cfg_attr(feature = "napi", napi(string_enum = "camelCase"))]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceFormat {
+    /// HTTP Archive layout with one entry per request/response pair
+    Har,
+    /// Request history and notifications as shown by the MCP Inspector
+    Inspector,
+}
+
+/// Helper functions to create and inspect events
 impl McpEvent {
     /// Create a log event
     pub fn log(level: &str, message: &str, server_id: Option<&str>) -> Self {
-        let payload = LogEvent {
+        Self::Log(LogEvent {
             level: level.to_string(),
             message: message.to_string(),
             server_id: server_id.map(|s| s.to_string()),
+            logger: None,
+            data: None,
+            timestamp: now_millis(),
+            sequence: 0,
+        })
+    }
+
+    /// Create a log event from an MCP log message sent by a server
+    pub fn server_log(
+        level: &str,
+        logger: Option<&str>,
+        data: &serde_json::Value,
+        server_id: &str,
+    ) -> Self {
+        let message = match data {
+            serde_json::Value::String(message) => message.clone(),
+            data => data.to_string(),
         };
-        
-        Self {
-            event_type: "log".to_string(),
-            payload: serde_json::to_string(&payload).unwrap_or_default(),
-        }
+        Self::Log(LogEvent {
+            level: level.to_string(),
+            message,
+            server_id: Some(server_id.to_string()),
+            logger: logger.map(|s| s.to_string()),
+            data: Some(data.to_string()),
+            timestamp: now_millis(),
+            sequence: 0,
+        })
     }
 
     /// Create an info log event
@@ -91,33 +956,145 @@ impl McpEvent {
         Self::log("info", message, server_id)
     }
 
+    /// Create a warning log event
+    pub fn warn(message: &str, server_id: Option<&str>) -> Self {
+        Self::log("warn", message, server_id)
+    }
+
     /// Create an error log event
     pub fn error(message: &str, server_id: Option<&str>) -> Self {
         Self::log("error", message, server_id)
     }
 
     /// Create a tools updated event
-    pub fn tools_updated(tools: Vec<ToolInfo>) -> Self {
-        let payload = ToolsUpdatedEvent { tools };
-        Self {
-            event_type: "toolsUpdated".to_string(),
-            payload: serde_json::to_string(&payload).unwrap_or_default(),
-        }
+    pub fn tools_updated(server_id: &str, tools: Vec<ToolInfo>) -> Self {
+        Self::ToolsUpdated(ToolsUpdatedEvent {
+            server_id: server_id.to_string(),
+            tools,
+            sequence: 0,
+        })
     }
 
     /// Create a server started event
     pub fn server_started(server_id: &str) -> Self {
-        Self {
-            event_type: "serverStarted".to_string(),
-            payload: serde_json::to_string(&serde_json::json!({ "serverId": server_id })).unwrap_or_default(),
-        }
+        Self::ServerStarted(ServerStartedEvent {
+            server_id: server_id.to_string(),
+            sequence: 0,
+        })
     }
 
     /// Create a server stopped event
     pub fn server_stopped(server_id: &str) -> Self {
-        Self {
-            event_type: "serverStopped".to_string(),
-            payload: serde_json::to_string(&serde_json::json!({ "serverId": server_id })).unwrap_or_default(),
+        Self::ServerStopped(ServerStoppedEvent {
+            server_id: server_id.to_string(),
+            sequence: 0,
+        })
+    }
+
+    /// Create a server crashed event
+    pub fn server_crashed(server_id: &str, reason: &str) -> Self {
+        Self::ServerCrashed(ServerCrashedEvent {
+            server_id: server_id.to_string(),
+            reason: reason.to_string(),
+            sequence: 0,
+        })
+    }
+
+    /// Create a progress event
+    pub fn progress(
+        server_id: &str,
+        progress_token: &str,
+        progress: u32,
+        total: Option<u32>,
+        message: Option<&str>,
+    ) -> Self {
+        Self::Progress(ProgressEvent {
+            server_id: server_id.to_string(),
+            progress_token: progress_token.to_string(),
+            progress,
+            total,
+            message: message.map(|s| s.to_string()),
+            sequence: 0,
+        })
+    }
+
+    /// Create a config reloaded event
+    pub fn config_reloaded(path: &str, changes: Vec<ApplyResult>, error: Option<String>) -> Self {
+        Self::ConfigReloaded(ConfigReloadedEvent {
+            path: path.to_string(),
+            changes,
+            error,
+            sequence: 0,
+        })
+    }
+
+    /// Create a server stats event
+    pub fn server_stats(server_id: &str, stats: ServerStats) -> Self {
+        Self::ServerStats(ServerStatsEvent {
+            server_id: server_id.to_string(),
+            stats,
+            sequence: 0,
+        })
+    }
+
+    /// Create a resource limit event
+    pub fn resource_limit(server_id: &str, breach: LimitBreach, action: Option<LimitAction>) -> Self {
+        Self::ResourceLimit(ResourceLimitEvent {
+            server_id: server_id.to_string(),
+            breach,
+            action,
+            sequence: 0,
+        })
+    }
+
+    /// TypeScript declarations of the event union and its discriminant
+    ///
+    /// NAPI-RS cannot derive it from the enum, so it is written to `index.d.ts`
+    /// by `npm run build:types` and checked by the `type_defs` test.
+    pub fn type_def() -> String {
+        let mut def = String::from(
+            "/**\n * Event emitted by the MCP manager\n *\n * Reaches TypeScript as the variant's object with an `eventType` discriminant.\n */\nexport type McpEvent =\n",
+        );
+        for (event_type, payload) in EVENT_TYPE_DEFS {
+            def.push_str(&format!("  | ({{ eventType: '{}' }} & {})\n", event_type, payload));
+        }
+        def.push_str("/** Discriminant of an event */\nexport type McpEventType = McpEvent['eventType']\n");
+        def
+    }
+
+    /// ID of the server the event relates to (if applicable)
+    pub fn server_id(&self) -> Option<&str> {
+        match self {
+            Self::Log(event) => event.server_id.as_deref(),
+            Self::ServerStarted(event) => Some(&event.server_id),
+            Self::ServerStopped(event) => Some(&event.server_id),
+            Self::ServerCrashed(event) => Some(&event.server_id),
+            Self::ToolsUpdated(event) => Some(&event.server_id),
+            Self::Progress(event) => Some(&event.server_id),
+            Self::Trace(event) => Some(&event.server_id),
+            Self::ConfigReloaded(_) => None,
+            Self::ServerStats(event) => Some(&event.server_id),
+            Self::ResourceLimit(event) => Some(&event.server_id),
         }
     }
 }
+
+#[cfg(feature = "napi")]
+impl TypeName for McpEvent {
+    fn type_name() -> &'static str {
+        "McpEvent"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+/// Current time in milliseconds since the Unix epoch
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
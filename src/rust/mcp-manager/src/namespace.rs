@@ -0,0 +1,57 @@
+use crate::models::{NamespacePolicy, ServerConfig, ToolInfo};
+use std::collections::HashSet;
+
+/// Separator between a prefix and the tool name in qualified names
+pub const QUALIFIED_NAME_SEPARATOR: &str = "__";
+
+/// Result of assigning qualified names to the tools of all servers
+pub struct QualifiedTools {
+    /// Tools that received a unique qualified name
+    pub tools: Vec<ToolInfo>,
+    /// Tools hidden because an earlier server already uses their qualified name
+    pub shadowed: Vec<ToolInfo>,
+}
+
+/// Assign qualified names to tools according to the namespacing policy
+///
+/// Servers must be given in start order. When two tools end up with the same
+/// qualified name, the tool of the server started first wins and the other one
+/// is reported as shadowed.
+pub fn qualify<'a>(
+    servers: impl IntoIterator<Item = (&'a ServerConfig, &'a [ToolInfo])>,
+    policy: NamespacePolicy,
+) -> QualifiedTools {
+    let mut taken = HashSet::new();
+    let mut tools = Vec::new();
+    let mut shadowed = Vec::new();
+
+    for (config, server_tools) in servers {
+        let prefix = match policy {
+            NamespacePolicy::ServerId => Some(config.id.as_str()),
+            NamespacePolicy::CustomPrefix => {
+                Some(config.tool_prefix.as_deref().unwrap_or(config.id.as_str()))
+            }
+            NamespacePolicy::FirstWins => None,
+        };
+
+        for tool in server_tools {
+            let qualified_name = match prefix {
+                Some(prefix) if !prefix.is_empty() => {
+                    format!("{}{}{}", prefix, QUALIFIED_NAME_SEPARATOR, tool.tool_name)
+                }
+                _ => tool.tool_name.clone(),
+            };
+            let tool = ToolInfo {
+                qualified_name: qualified_name.clone(),
+                ..tool.clone()
+            };
+            if taken.insert(qualified_name) {
+                tools.push(tool);
+            } else {
+                shadowed.push(tool);
+            }
+        }
+    }
+
+    QualifiedTools { tools, shadowed }
+}
@@ -0,0 +1,129 @@
+use crate::models::ServerStats;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// How often `serverStats` events are emitted, when not configured
+pub const DEFAULT_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Process information for walking process trees and measuring their resource usage
+pub struct ProcessMonitor {
+    system: System,
+    /// CPU time in milliseconds of each measured process, and when it was read
+    cpu_times: HashMap<Pid, (u64, Instant)>,
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            cpu_times: HashMap::new(),
+        }
+    }
+
+    /// Refresh the list of processes with nothing but what is needed to walk their tree
+    ///
+    /// Threads are left out, so they are not mistaken for child processes.
+    pub fn refresh_process_list(&mut self) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().without_tasks(),
+        );
+        let system = &self.system;
+        self.cpu_times.retain(|pid, _| system.process(*pid).is_some());
+    }
+
+    /// A process as of the last refresh
+    pub fn process(&self, pid: Pid) -> Option<&Process> {
+        self.system.process(pid)
+    }
+
+    /// Descendants of a process as of the last refresh, parents before their children
+    pub fn descendants(&self, root: Pid) -> Vec<Pid> {
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        for process in self.system.processes().values() {
+            if process.thread_kind().is_some() {
+                continue;
+            }
+            if let Some(parent) = process.parent() {
+                children.entry(parent).or_default().push(process.pid());
+            }
+        }
+
+        let mut found = Vec::new();
+        let mut pending = vec![root];
+        while let Some(parent) = pending.pop() {
+            for &child in children.get(&parent).into_iter().flatten() {
+                // Guard against PID reuse creating a cycle
+                if child != root && !found.contains(&child) {
+                    found.push(child);
+                    pending.push(child);
+                }
+            }
+        }
+        found
+    }
+
+    /// Whether a process was measured before, so its CPU usage can be told
+    pub fn was_measured(&self, pid: u32) -> bool {
+        self.cpu_times.contains_key(&Pid::from_u32(pid))
+    }
+
+    /// Measure the resource usage of the process trees rooted at the given PIDs
+    ///
+    /// Only the processes of those trees are refreshed. CPU usage of a process is
+    /// averaged since its previous measurement, and 0 when it is measured the first time.
+    pub fn measure(&mut self, roots: &[u32]) -> HashMap<u32, ServerStats> {
+        self.refresh_process_list();
+        let trees: Vec<(u32, Vec<Pid>)> = roots
+            .iter()
+            .map(|&root| {
+                let pid = Pid::from_u32(root);
+                let mut tree = vec![pid];
+                tree.extend(self.descendants(pid));
+                (root, tree)
+            })
+            .collect();
+
+        let pids: Vec<Pid> = trees.iter().flat_map(|(_, tree)| tree.iter().copied()).collect();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory().with_tasks(),
+        );
+        let now = Instant::now();
+
+        let mut stats = HashMap::with_capacity(trees.len());
+        for (root, tree) in trees {
+            let mut sum = ServerStats {
+                pid: root,
+                child_count: tree.len() as u32 - 1,
+                ..Default::default()
+            };
+            let mut open_files = Some(0);
+            for pid in tree {
+                let Some(process) = self.system.process(pid) else {
+                    continue;
+                };
+                let cpu_time = process.accumulated_cpu_time();
+                if let Some((previous, at)) = self.cpu_times.insert(pid, (cpu_time, now)) {
+                    let elapsed = now.duration_since(at).as_millis();
+                    if elapsed > 0 {
+                        sum.cpu_percent +=
+                            cpu_time.saturating_sub(previous) as f64 * 100.0 / elapsed as f64;
+                    }
+                }
+                sum.memory_bytes += process.memory() as f64;
+                sum.virtual_memory_bytes += process.virtual_memory() as f64;
+                sum.thread_count += process.tasks().map_or(1, |tasks| tasks.len().max(1) as u32);
+                open_files = open_files
+                    .zip(process.open_files())
+                    .map(|(sum, count)| sum + count as u32);
+            }
+            sum.open_files = open_files;
+            stats.insert(root, sum);
+        }
+        stats
+    }
+}
@@ -0,0 +1,196 @@
+use crate::error::{Error, Result};
+use lazy_static::lazy_static;
+use regex::{Regex, RegexSet};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Text secrets are replaced with in events, traces and log files
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Known values shorter than this are not redacted, as they would mask ordinary text
+const MIN_REDACTED_LENGTH: usize = 4;
+
+/// Patterns of well-known credentials, masked unless disabled in the manager options
+///
+/// When a pattern has a `secret` group only that group is masked, so the text
+/// around it (e.g. `Bearer ` or `password=`) stays readable.
+const DEFAULT_PATTERNS: &[&str] = &[
+    // Authorization headers
+    r"(?i)\b(?:bearer|token|basic)\s+(?P<secret>[A-Za-z0-9\-._~+/]{12,}=*)",
+    // AWS access key IDs and secret access keys
+    r"\b(?:AKIA|ASIA|AROA|AIDA)[0-9A-Z]{16}\b",
+    r"(?i)aws_?secret_?access_?key\W{0,3}(?P<secret>[A-Za-z0-9/+=]{40})",
+    // GitHub, GitLab and Slack tokens
+    r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,}|glpat-[A-Za-z0-9\-_]{20,})",
+    r"\bxox[abposr]-[A-Za-z0-9\-]{10,}",
+    // OpenAI, Anthropic and Stripe style API keys
+    r"\b(?:sk|rk|pk)-(?:ant-|proj-|live_|test_)?[A-Za-z0-9\-_]{20,}",
+    r"\b(?:sk|rk|pk)_(?:live|test)_[A-Za-z0-9]{16,}",
+    // Google API keys
+    r"\bAIza[0-9A-Za-z\-_]{35}",
+    // JSON Web Tokens
+    r"\beyJ[A-Za-z0-9\-_]{8,}\.eyJ[A-Za-z0-9\-_]{8,}\.[A-Za-z0-9\-_]{8,}",
+    // PEM private keys
+    r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?(?:-----END [A-Z ]*PRIVATE KEY-----|$)",
+    // Credentials embedded in URLs
+    r"\b[a-z][a-z0-9+.\-]*://[^/\s:@]+:(?P<secret>[^/\s@]+)@",
+    // Assignments of secret-looking names, also inside (escaped) JSON
+    r#"(?i)\b[a-z0-9_\-]*(?:api[_\-]?key|secret|token|password|passwd|pwd)\b[\\"']{0,2}\s*[:=]\s*[\\"']{0,2}(?P<secret>[^\s\\"',;&}]{6,})"#,
+];
+
+lazy_static! {
+    /// Env variable names whose values are treated as secrets even without a reference
+    static ref SENSITIVE_NAME: Regex =
+        Regex::new(r"(?i)key|token|secret|passw(?:or)?d|credential|auth|cookie|session|private")
+            .unwrap();
+}
+
+/// Whether an env variable's value should be treated as a secret because of its name
+pub fn is_sensitive_name(name: &str) -> bool {
+    SENSITIVE_NAME.is_match(name)
+}
+
+/// Masks secrets in the text the manager reports
+///
+/// Secrets are known values, such as those resolved from a server's env, and
+/// anything matching the configured patterns.
+pub struct Redactor {
+    /// Known secret values by server ID, longest first, with the start order of their server
+    values: RwLock<HashMap<String, (u64, Vec<String>)>>,
+    /// Patterns of secrets, with a quick check of which ones match a text
+    patterns: Vec<Regex>,
+    pattern_set: RegexSet,
+}
+
+impl Redactor {
+    /// Create a redactor for the built-in patterns (unless disabled) and extra patterns
+    pub fn new(default_patterns: bool, extra_patterns: &[String]) -> Result<Self> {
+        let defaults = if default_patterns { DEFAULT_PATTERNS } else { &[] };
+        let sources: Vec<&str> = defaults
+            .iter()
+            .copied()
+            .chain(extra_patterns.iter().map(String::as_str))
+            .collect();
+        let patterns = sources
+            .iter()
+            .map(|source| {
+                Regex::new(source).map_err(|e| {
+                    Error::ConfigError(format!("Invalid redaction pattern '{}': {}", source, e))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let pattern_set = RegexSet::new(&sources)
+            .map_err(|e| Error::ConfigError(format!("Invalid redaction patterns: {}", e)))?;
+        Ok(Self {
+            values: RwLock::new(HashMap::new()),
+            patterns,
+            pattern_set,
+        })
+    }
+
+    /// Replace the known secret values of a server
+    ///
+    /// `order` tells the server apart from a later one started with the same ID.
+    pub fn set_values(&self, server_id: &str, order: u64, mut values: Vec<String>) {
+        values.retain(|value| value.len() >= MIN_REDACTED_LENGTH);
+        // Longer values first, so a secret containing another is masked as a whole
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+        values.dedup();
+        let mut all = self.values.write().unwrap();
+        if values.is_empty() {
+            all.remove(server_id);
+        } else {
+            all.insert(server_id.to_string(), (order, values));
+        }
+    }
+
+    /// Forget the known secret values of a server, unless a later server with its ID replaced them
+    pub fn remove_values(&self, server_id: &str, order: u64) {
+        let mut all = self.values.write().unwrap();
+        if all.get(server_id).is_some_and(|(owner, _)| *owner == order) {
+            all.remove(server_id);
+        }
+    }
+
+    /// Mask every secret in a text
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for value in self.values.read().unwrap().values().flat_map(|(_, values)| values) {
+            if text.contains(value.as_str()) {
+                text = Cow::Owned(text.replace(value.as_str(), REDACTED));
+            }
+        }
+        for index in self.pattern_set.matches(&text).into_iter().collect::<Vec<_>>() {
+            if let Cow::Owned(redacted) = mask_matches(&self.patterns[index], &text) {
+                text = Cow::Owned(redacted);
+            }
+        }
+        text
+    }
+
+    /// Mask every secret in a string field
+    pub fn redact_field(&self, text: &mut String) {
+        if let Cow::Owned(redacted) = self.redact(text) {
+            *text = redacted;
+        }
+    }
+
+    /// Mask every secret in the strings of a JSON value
+    pub fn redact_json<'a>(&self, value: &'a Value) -> Cow<'a, Value> {
+        match value {
+            Value::String(text) => match self.redact(text) {
+                Cow::Owned(redacted) => Cow::Owned(Value::String(redacted)),
+                Cow::Borrowed(_) => Cow::Borrowed(value),
+            },
+            Value::Array(items) => {
+                let redacted: Vec<Cow<Value>> =
+                    items.iter().map(|item| self.redact_json(item)).collect();
+                if redacted.iter().all(|item| matches!(item, Cow::Borrowed(_))) {
+                    return Cow::Borrowed(value);
+                }
+                Cow::Owned(Value::Array(redacted.into_iter().map(Cow::into_owned).collect()))
+            }
+            Value::Object(entries) => {
+                let redacted: Vec<(&String, Cow<Value>)> = entries
+                    .iter()
+                    .map(|(key, entry)| (key, self.redact_json(entry)))
+                    .collect();
+                if redacted.iter().all(|(_, entry)| matches!(entry, Cow::Borrowed(_))) {
+                    return Cow::Borrowed(value);
+                }
+                Cow::Owned(Value::Object(
+                    redacted
+                        .into_iter()
+                        .map(|(key, entry)| (key.clone(), entry.into_owned()))
+                        .collect(),
+                ))
+            }
+            _ => Cow::Borrowed(value),
+        }
+    }
+}
+
+/// Mask the matches of a pattern, or only their `secret` group if the pattern has one
+fn mask_matches<'a>(pattern: &Regex, text: &'a str) -> Cow<'a, str> {
+    let mut masked = String::new();
+    let mut last = 0;
+    for captures in pattern.captures_iter(text) {
+        let Some(secret) = captures.name("secret").or_else(|| captures.get(0)) else {
+            continue;
+        };
+        // Skip what an earlier pattern already masked
+        if secret.as_str() == REDACTED || secret.is_empty() {
+            continue;
+        }
+        masked.push_str(&text[last..secret.start()]);
+        masked.push_str(REDACTED);
+        last = secret.end();
+    }
+    if last == 0 {
+        return Cow::Borrowed(text);
+    }
+    masked.push_str(&text[last..]);
+    Cow::Owned(masked)
+}
@@ -0,0 +1,318 @@
+use crate::error::{Error, Result};
+use crate::models::{Direction, ReplayConfig, ReplayMatcher};
+use rmcp::service::{RoleClient, RxJsonRpcMessage, TxJsonRpcMessage};
+use rmcp::transport::Transport;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Requests that are matched by method alone, whatever the matcher
+const LIFECYCLE_METHODS: &[&str] = &["initialize", "ping"];
+
+/// One line of a recording file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedMessage {
+    /// Whether the manager sent or received the message
+    direction: Direction,
+    /// Milliseconds since the recording started
+    offset_ms: u64,
+    /// The JSON-RPC message
+    message: Value,
+}
+
+/// Writes the JSON-RPC exchange of a server to a JSON Lines file
+pub struct Recorder {
+    file: Mutex<BufWriter<File>>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Create or truncate the recording file
+    pub fn new(path: &str) -> Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            Error::ConfigError(format!("Failed to create recording '{}': {}", path, e))
+        })?;
+        Ok(Self {
+            file: Mutex::new(BufWriter::new(file)),
+            started: Instant::now(),
+        })
+    }
+
+    /// Append a message to the recording
+    ///
+    /// Failures are only traced; a broken recording must never affect the server.
+    pub fn record(&self, direction: Direction, message: &Value) {
+        let line = RecordedMessage {
+            direction,
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            message: message.clone(),
+        };
+        let mut file = self.file.lock().unwrap();
+        let written = serde_json::to_writer(&mut *file, &line)
+            .map_err(std::io::Error::from)
+            .and_then(|()| file.write_all(b"\n"))
+            .and_then(|()| file.flush());
+        if let Err(e) = written {
+            warn!("Failed to write recording: {}", e);
+        }
+    }
+}
+
+/// A recorded request together with everything the server sent while answering it
+struct Exchange {
+    /// The request as the manager sent it
+    request: Value,
+    /// Messages the server sent in response, in recorded order
+    replies: Vec<Value>,
+    /// Whether the exchange was already played back
+    used: bool,
+}
+
+/// Transport that plays a recording back instead of talking to a server
+///
+/// Each request the manager sends is matched against the recorded requests;
+/// the first unused match is answered with its recorded response and the
+/// notifications and server requests recorded while it was pending. Once all
+/// matches are used, the last one is played again. Requests without a match
+/// are answered with a JSON-RPC error.
+pub struct ReplayTransport {
+    exchanges: Vec<Exchange>,
+    matcher: ReplayMatcher,
+    ignore_params: Vec<String>,
+    tx: mpsc::UnboundedSender<Value>,
+    rx: mpsc::UnboundedReceiver<Value>,
+}
+
+impl ReplayTransport {
+    /// Load a recording for playback
+    pub fn open(config: &ReplayConfig) -> Result<Self> {
+        let file = File::open(&config.path).map_err(|e| {
+            Error::ConfigError(format!("Failed to open recording '{}': {}", config.path, e))
+        })?;
+
+        let mut messages = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| {
+                Error::ConfigError(format!("Failed to read recording '{}': {}", config.path, e))
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message: RecordedMessage = serde_json::from_str(&line).map_err(|e| {
+                Error::ConfigError(format!(
+                    "Invalid recording '{}' at line {}: {}",
+                    config.path,
+                    index + 1,
+                    e
+                ))
+            })?;
+            messages.push(message);
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            exchanges: group_exchanges(messages),
+            matcher: config.matcher.unwrap_or(ReplayMatcher::Exact),
+            ignore_params: config.ignore_params.clone().unwrap_or_default(),
+            tx,
+            rx,
+        })
+    }
+
+    /// Queue the recorded replies to a request
+    fn answer(&mut self, request: &Value) {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let matching: Vec<usize> = (0..self.exchanges.len())
+            .filter(|&index| self.matches(&self.exchanges[index].request, request))
+            .collect();
+        let chosen = matching
+            .iter()
+            .copied()
+            .find(|&index| !self.exchanges[index].used)
+            .or_else(|| matching.last().copied());
+
+        let Some(index) = chosen else {
+            let _ = self.tx.send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": -32603,
+                    "message": format!("No recorded response matches request '{}'", method),
+                },
+            }));
+            return;
+        };
+
+        let exchange = &mut self.exchanges[index];
+        exchange.used = true;
+        let recorded_id = exchange.request.get("id").cloned().unwrap_or(Value::Null);
+        let recorded_token = exchange.request.pointer("/params/_meta/progressToken").cloned();
+        let token = request.pointer("/params/_meta/progressToken").cloned();
+
+        for reply in &exchange.replies {
+            let mut reply = reply.clone();
+            // Responses and progress carry the IDs of this session, not the recorded one
+            if reply.get("method").is_none() && reply.get("id") == Some(&recorded_id) {
+                reply["id"] = id.clone();
+            }
+            if let (Some(recorded_token), Some(token)) = (&recorded_token, &token) {
+                if let Some(progress_token) = reply.pointer_mut("/params/progressToken") {
+                    if progress_token == recorded_token {
+                        *progress_token = token.clone();
+                    }
+                }
+            }
+            let _ = self.tx.send(reply);
+        }
+    }
+
+    /// Whether a recorded request answers an actual one under the configured matcher
+    fn matches(&self, recorded: &Value, actual: &Value) -> bool {
+        let method = actual.get("method").and_then(Value::as_str);
+        if recorded.get("method").and_then(Value::as_str) != method {
+            return false;
+        }
+        if method.is_some_and(|method| LIFECYCLE_METHODS.contains(&method)) {
+            return true;
+        }
+
+        let recorded = self.comparable_params(recorded);
+        let actual = self.comparable_params(actual);
+        match self.matcher {
+            ReplayMatcher::Method => true,
+            ReplayMatcher::Exact => recorded == actual,
+            ReplayMatcher::Subset => is_subset(&recorded, &actual),
+        }
+    }
+
+    /// Params of a request without `_meta` and the ignored fields
+    fn comparable_params(&self, request: &Value) -> Value {
+        let mut params = request.get("params").cloned().unwrap_or(Value::Null);
+        if let Value::Object(map) = &mut params {
+            map.remove("_meta");
+        }
+        for pointer in &self.ignore_params {
+            remove_pointer(&mut params, pointer);
+        }
+        params
+    }
+}
+
+impl Transport<RoleClient> for ReplayTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = std::result::Result<(), Self::Error>> + Send + 'static {
+        let result = serde_json::to_value(&item).map(|message| {
+            // Notifications and responses to server requests need no answer
+            let messages = match message {
+                Value::Array(messages) => messages,
+                message => vec![message],
+            };
+            for message in messages {
+                if message.get("method").is_some() && message.get("id").is_some() {
+                    self.answer(&message);
+                }
+            }
+        });
+        std::future::ready(result.map_err(std::io::Error::from))
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleClient>> {
+        loop {
+            let message = self.rx.recv().await?;
+            match serde_json::from_value(message) {
+                Ok(message) => return Some(message),
+                Err(e) => warn!("Skipping unreadable recorded message: {}", e),
+            }
+        }
+    }
+
+    fn close(&mut self) -> impl Future<Output = std::result::Result<(), Self::Error>> + Send {
+        self.rx.close();
+        std::future::ready(Ok(()))
+    }
+}
+
+/// Split a recording into requests and the replies sent while they were pending
+///
+/// A server message belongs to the request whose ID it answers, otherwise to
+/// the most recent request sent before it.
+fn group_exchanges(messages: Vec<RecordedMessage>) -> Vec<Exchange> {
+    let mut exchanges: Vec<Exchange> = Vec::new();
+
+    for recorded in messages {
+        let message = recorded.message;
+        let is_request = message.get("method").is_some() && message.get("id").is_some();
+        match recorded.direction {
+            Direction::Outgoing if is_request => exchanges.push(Exchange {
+                request: message,
+                replies: Vec::new(),
+                used: false,
+            }),
+            Direction::Outgoing => {}
+            Direction::Incoming => {
+                let answered = match message.get("method") {
+                    None => message.get("id").and_then(|id| {
+                        exchanges
+                            .iter()
+                            .rposition(|exchange| exchange.request.get("id") == Some(id))
+                    }),
+                    Some(_) => None,
+                };
+                if let Some(index) = answered.or_else(|| exchanges.len().checked_sub(1)) {
+                    exchanges[index].replies.push(message);
+                }
+            }
+        }
+    }
+    exchanges
+}
+
+/// Whether every field of `expected` is present with the same value in `actual`
+fn is_subset(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual_value| is_subset(value, actual_value))
+        }),
+        (expected, actual) => expected == actual,
+    }
+}
+
+/// Remove the value a JSON pointer refers to, if present
+fn remove_pointer(value: &mut Value, pointer: &str) {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    let key = key.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.remove(&key);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(index) = key.parse::<usize>() {
+                if index < items.len() {
+                    items.remove(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,386 @@
+use crate::error::{Error, Result};
+use crate::models::{SandboxConfig, SandboxNetwork};
+
+/// Restrictions for one server process, prepared by the manager and applied
+/// in the forked child right before it runs the server command
+///
+/// Everything that can fail for a configuration reason (missing paths, limits
+/// above what the manager may grant, a kernel without Landlock) is checked in
+/// [`Sandbox::prepare`], so the child only makes a few system calls.
+#[derive(Clone)]
+pub struct Sandbox {
+    #[cfg(target_os = "linux")]
+    inner: linux::Sandbox,
+}
+
+impl Sandbox {
+    /// Check a sandbox configuration and prepare what the child needs to apply it
+    pub fn prepare(config: &SandboxConfig, command: &str) -> Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            Ok(Self {
+                inner: linux::Sandbox::prepare(config, command)?,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (config, command);
+            Err(Error::ProcessStartError(
+                "Sandboxing is only supported on Linux".to_string(),
+            ))
+        }
+    }
+
+    /// Restrict the calling process
+    ///
+    /// Runs between fork and exec, so it only makes async-signal-safe system calls.
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self) -> std::io::Result<()> {
+        self.inner.apply()
+    }
+
+    /// Restriction that could not be applied and is skipped, to be reported as a warning
+    pub fn warning(&self) -> Option<&str> {
+        #[cfg(target_os = "linux")]
+        {
+            self.inner.warning.as_deref()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{Error, Result, SandboxConfig, SandboxNetwork};
+    use landlock::{
+        ABI, Access, AccessFs, BitFlags, CompatLevel, Compatible, PathBeneath, PathFd, Ruleset,
+        RulesetAttr, RulesetCreatedAttr,
+    };
+    use std::ffi::CStr;
+    use std::os::fd::{AsRawFd, OwnedFd};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, OnceLock};
+
+    #[cfg(target_env = "gnu")]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(target_env = "gnu"))]
+    type Resource = libc::c_int;
+
+    /// Directories every program needs to read to be loaded and run
+    ///
+    /// Those missing on this system are skipped.
+    const SYSTEM_READ_PATHS: &[&str] = &[
+        "/usr", "/lib", "/lib32", "/lib64", "/bin", "/sbin", "/etc", "/proc", "/dev",
+    ];
+
+    /// Files every program may write
+    const SYSTEM_WRITE_PATHS: &[&str] = &["/dev/null"];
+
+    /// Landlock ABI the kernel must support at least
+    ///
+    /// Rights of newer ABIs are restricted as well when the kernel supports them.
+    const MIN_ABI: ABI = ABI::V1;
+    const BEST_ABI: ABI = ABI::V5;
+
+    #[derive(Clone)]
+    pub struct Sandbox {
+        /// Resource limits, each set as both the soft and the hard limit
+        limits: Vec<(Resource, libc::rlim_t)>,
+        no_new_privs: bool,
+        /// Landlock ruleset to enforce, shared as the sandbox is cloned into the spawn hook
+        landlock: Option<Arc<OwnedFd>>,
+        /// ID mappings of the user namespace the server is isolated from the network in
+        namespace: Option<IdMaps>,
+        /// Why network isolation is skipped
+        pub warning: Option<String>,
+    }
+
+    /// Contents of `/proc/self/{uid,gid}_map`, mapping the server's IDs to themselves
+    #[derive(Clone)]
+    struct IdMaps {
+        uid_map: Vec<u8>,
+        gid_map: Vec<u8>,
+    }
+
+    impl Sandbox {
+        pub fn prepare(config: &SandboxConfig, command: &str) -> Result<Self> {
+            let mut limits = Vec::new();
+            for (resource, name, value) in [
+                (
+                    libc::RLIMIT_AS,
+                    "maxMemoryMb",
+                    config.max_memory_mb.map(|mb| u64::from(mb) * 1024 * 1024),
+                ),
+                (
+                    libc::RLIMIT_NOFILE,
+                    "maxOpenFiles",
+                    config.max_open_files.map(u64::from),
+                ),
+                (
+                    libc::RLIMIT_CPU,
+                    "maxCpuSeconds",
+                    config.max_cpu_seconds.map(u64::from),
+                ),
+                (
+                    libc::RLIMIT_NPROC,
+                    "maxProcesses",
+                    config.max_processes.map(u64::from),
+                ),
+            ] {
+                if let Some(value) = value {
+                    limits.push((resource, checked_limit(resource, name, value)?));
+                }
+            }
+
+            let landlock = if config.read_paths.is_some() || config.write_paths.is_some() {
+                Some(Arc::new(landlock_ruleset(config, command)?))
+            } else {
+                None
+            };
+
+            let mut warning = None;
+            let namespace = match config.network {
+                Some(SandboxNetwork::None) => match network_namespaces() {
+                    Ok(()) => {
+                        // SAFETY: these calls cannot fail
+                        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                        Some(IdMaps {
+                            uid_map: format!("{} {} 1\n", uid, uid).into_bytes(),
+                            gid_map: format!("{} {} 1\n", gid, gid).into_bytes(),
+                        })
+                    }
+                    Err(e) => {
+                        warning = Some(format!(
+                            "Network namespaces are unavailable ({}); the server keeps host networking",
+                            e
+                        ));
+                        None
+                    }
+                },
+                Some(SandboxNetwork::Host) | None => None,
+            };
+
+            Ok(Self {
+                limits,
+                // Landlock can only be enforced by processes without new privileges
+                no_new_privs: landlock.is_some() || !config.allow_new_privileges.unwrap_or(false),
+                landlock,
+                namespace,
+                warning,
+            })
+        }
+
+        pub fn apply(&self) -> std::io::Result<()> {
+            // Set up the namespaces first, as Landlock forbids writing the ID maps
+            if let Some(maps) = &self.namespace {
+                // SAFETY: plain system call
+                if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                write_proc_file(c"/proc/self/setgroups", b"deny")?;
+                write_proc_file(c"/proc/self/uid_map", &maps.uid_map)?;
+                write_proc_file(c"/proc/self/gid_map", &maps.gid_map)?;
+            }
+            for &(resource, value) in &self.limits {
+                let limit = libc::rlimit {
+                    rlim_cur: value,
+                    rlim_max: value,
+                };
+                // SAFETY: plain system call on a valid struct
+                if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            // SAFETY: plain system calls; the ruleset fd stays open while `self` is alive
+            unsafe {
+                if self.no_new_privs && libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if let Some(ruleset) = &self.landlock {
+                    if libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0) != 0
+                    {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Write a file in `/proc` without allocating
+    fn write_proc_file(path: &CStr, content: &[u8]) -> std::io::Result<()> {
+        // SAFETY: plain system calls on a valid path and buffer
+        unsafe {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let written = libc::write(fd, content.as_ptr().cast(), content.len());
+            let error = std::io::Error::last_os_error();
+            libc::close(fd);
+            if written != content.len() as isize {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether this process may create user and network namespaces
+    ///
+    /// A forked child tries to, as `unshare` fails in multi-threaded processes.
+    /// The result is kept, since what allows it rarely changes while the manager runs.
+    fn network_namespaces() -> std::io::Result<()> {
+        static AVAILABLE: OnceLock<i32> = OnceLock::new();
+        let errno = *AVAILABLE.get_or_init(|| {
+            // SAFETY: the child only makes async-signal-safe system calls before exiting
+            unsafe {
+                let pid = libc::fork();
+                if pid == 0 {
+                    let errno = if libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) == 0 {
+                        0
+                    } else {
+                        last_errno()
+                    };
+                    libc::_exit(errno);
+                }
+                if pid < 0 {
+                    return last_errno();
+                }
+                let mut status = 0;
+                if libc::waitpid(pid, &mut status, 0) != pid || !libc::WIFEXITED(status) {
+                    return libc::ECHILD;
+                }
+                libc::WEXITSTATUS(status)
+            }
+        });
+        match errno {
+            0 => Ok(()),
+            errno => Err(std::io::Error::from_raw_os_error(errno)),
+        }
+    }
+
+    fn last_errno() -> i32 {
+        std::io::Error::last_os_error().raw_os_error().unwrap_or(libc::EINVAL)
+    }
+
+    /// Check a resource limit against the manager's own hard limit, which it cannot raise
+    fn checked_limit(resource: Resource, name: &str, value: u64) -> Result<libc::rlim_t> {
+        if value == 0 {
+            return Err(Error::ProcessStartError(format!(
+                "Sandbox option {} must be greater than 0",
+                name
+            )));
+        }
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: plain system call writing to a valid struct
+        if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+            return Err(Error::ProcessStartError(format!(
+                "Failed to read the current limit for sandbox option {}: {}",
+                name,
+                std::io::Error::last_os_error()
+            )));
+        }
+        if current.rlim_max != libc::RLIM_INFINITY && value > current.rlim_max {
+            return Err(Error::ProcessStartError(format!(
+                "Sandbox option {} ({}) exceeds the hard limit of the manager ({})",
+                name, value, current.rlim_max
+            )));
+        }
+        Ok(value)
+    }
+
+    /// Create a Landlock ruleset allowing the configured paths, the command and the system paths
+    fn landlock_ruleset(config: &SandboxConfig, command: &str) -> Result<OwnedFd> {
+        let unsupported = |e: landlock::RulesetError| {
+            Error::ProcessStartError(format!(
+                "Landlock filesystem sandboxing is not available: {}",
+                e
+            ))
+        };
+        let read = AccessFs::from_read(BEST_ABI);
+        let write = AccessFs::from_all(BEST_ABI);
+
+        let mut rules = Vec::new();
+        let system_paths = SYSTEM_READ_PATHS
+            .iter()
+            .map(|path| (*path, read))
+            .chain(SYSTEM_WRITE_PATHS.iter().map(|path| (*path, write)));
+        for (path, access) in system_paths {
+            if let Ok(rule) = path_rule(Path::new(path), access, "system") {
+                rules.push(rule);
+            }
+        }
+        if let Some(command) = resolve_command(command) {
+            rules.push(path_rule(&command, read, "command")?);
+        }
+        let configured = config
+            .read_paths
+            .iter()
+            .flatten()
+            .map(|path| (path, read, "readPaths"))
+            .chain(config.write_paths.iter().flatten().map(|path| (path, write, "writePaths")));
+        for (path, access, name) in configured {
+            rules.push(path_rule(Path::new(path), access, name)?);
+        }
+
+        let ruleset = Ruleset::default()
+            .set_compatibility(CompatLevel::HardRequirement)
+            .handle_access(AccessFs::from_all(MIN_ABI))
+            .map_err(unsupported)?
+            .set_compatibility(CompatLevel::BestEffort)
+            .handle_access(AccessFs::from_all(BEST_ABI))
+            .map_err(unsupported)?
+            .create()
+            .map_err(unsupported)?
+            .add_rules(rules.into_iter().map(Ok::<_, landlock::RulesetError>))
+            .map_err(|e| {
+                Error::ProcessStartError(format!("Failed to create the Landlock ruleset: {}", e))
+            })?;
+        Option::<OwnedFd>::from(ruleset).ok_or_else(|| {
+            Error::ProcessStartError(
+                "Landlock filesystem sandboxing is not supported by this kernel".to_string(),
+            )
+        })
+    }
+
+    /// Allow access beneath a path, failing clearly when it does not exist
+    fn path_rule(
+        path: &Path,
+        access: BitFlags<AccessFs>,
+        name: &str,
+    ) -> Result<PathBeneath<PathFd>> {
+        let fd = PathFd::new(path).map_err(|e| {
+            Error::ProcessStartError(format!(
+                "Sandbox {} path '{}' cannot be opened: {}",
+                name,
+                path.display(),
+                e
+            ))
+        })?;
+        // Rights on directory entries cannot be granted for a single file
+        let access = if path.is_file() {
+            access & AccessFs::from_file(BEST_ABI)
+        } else {
+            access
+        };
+        Ok(PathBeneath::new(fd, access))
+    }
+
+    /// Find the file a command runs, looking it up in `PATH` when it has no directory
+    fn resolve_command(command: &str) -> Option<PathBuf> {
+        let command = Path::new(command);
+        if command.components().count() > 1 {
+            return command.canonicalize().ok();
+        }
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(command))
+            .find(|path| path.is_file())
+            .and_then(|path| path.canonicalize().ok())
+    }
+}
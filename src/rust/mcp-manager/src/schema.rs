@@ -0,0 +1,336 @@
+use serde_json::{Map, Value};
+
+/// Keywords that only carry metadata for validators and are never useful to a model
+const META_KEYWORDS: &[&str] = &["$schema", "$id", "$defs", "definitions", "$comment"];
+
+/// Schema keywords understood by Gemini's OpenAPI-based function declarations
+const GEMINI_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "properties",
+    "required",
+    "items",
+    "minItems",
+    "maxItems",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "propertyOrdering",
+];
+
+/// Replace every local `$ref` in a schema with the definition it points to
+///
+/// Recursive references are cut off with a plain object schema, since providers
+/// cannot represent them.
+pub fn inline_refs(schema: &Value) -> Value {
+    let mut stack = Vec::new();
+    inline_refs_inner(schema, schema, &mut stack)
+}
+
+fn inline_refs_inner(root: &Value, node: &Value, stack: &mut Vec<String>) -> Value {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                let mut resolved = match resolve_ref(root, reference) {
+                    Some(target) if !stack.contains(reference) => {
+                        stack.push(reference.clone());
+                        let inlined = inline_refs_inner(root, target, stack);
+                        stack.pop();
+                        inlined
+                    }
+                    _ => serde_json::json!({ "type": "object" }),
+                };
+
+                // Sibling keywords (usually a description) override the referenced schema
+                if let Value::Object(resolved_map) = &mut resolved {
+                    for (key, value) in map {
+                        if key != "$ref" {
+                            resolved_map.insert(key.clone(), inline_refs_inner(root, value, stack));
+                        }
+                    }
+                }
+                return resolved;
+            }
+
+            Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), inline_refs_inner(root, value, stack)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| inline_refs_inner(root, item, stack))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Resolve a JSON pointer reference relative to the schema root
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+    root.pointer(pointer)
+}
+
+/// Keywords whose value is a subschema or an array of subschemas
+const SUBSCHEMA_KEYWORDS: &[&str] = &[
+    "items",
+    "prefixItems",
+    "additionalItems",
+    "additionalProperties",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contains",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+    "anyOf",
+    "oneOf",
+    "allOf",
+];
+
+/// Keywords whose value maps names to subschemas
+const SUBSCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+];
+
+/// Remove validator-only metadata keywords from every schema node of a schema
+///
+/// Only subschemas are visited, so property names and values such as `enum`
+/// or `default` are left alone even when they look like a keyword.
+pub fn strip_meta(schema: &mut Value) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+    for keyword in META_KEYWORDS {
+        map.remove(*keyword);
+    }
+    for (key, value) in map.iter_mut() {
+        if SUBSCHEMA_KEYWORDS.contains(&key.as_str()) {
+            match value {
+                Value::Array(items) => items.iter_mut().for_each(strip_meta),
+                other => strip_meta(other),
+            }
+        } else if SUBSCHEMA_MAP_KEYWORDS.contains(&key.as_str()) {
+            if let Value::Object(subschemas) = value {
+                subschemas.values_mut().for_each(strip_meta);
+            }
+        }
+    }
+}
+
+/// Make sure the root of a schema is a plain object schema
+///
+/// Providers reject top-level `oneOf`/`anyOf`/`allOf`, so their variants are
+/// merged into a single object whose properties are the union of all variants
+/// and whose required list is what every variant requires.
+pub fn normalize_root(schema: Value) -> Value {
+    let mut map = match schema {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+
+    for keyword in ["oneOf", "anyOf", "allOf"] {
+        let Some(Value::Array(variants)) = map.remove(keyword) else {
+            continue;
+        };
+        let mut properties = match map.remove("properties") {
+            Some(Value::Object(properties)) => properties,
+            _ => Map::new(),
+        };
+        let mut required: Option<Vec<Value>> = match map.remove("required") {
+            Some(Value::Array(required)) => Some(required),
+            _ => None,
+        };
+
+        for variant in variants {
+            let Value::Object(variant) = variant else {
+                continue;
+            };
+            if let Some(Value::Object(variant_properties)) = variant.get("properties") {
+                for (key, value) in variant_properties {
+                    properties.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+            let variant_required = match variant.get("required") {
+                Some(Value::Array(items)) => items.clone(),
+                _ => Vec::new(),
+            };
+            required = Some(match (required, keyword) {
+                (None, _) => variant_required,
+                (Some(mut current), "allOf") => {
+                    for item in variant_required {
+                        if !current.contains(&item) {
+                            current.push(item);
+                        }
+                    }
+                    current
+                }
+                (Some(current), _) => current
+                    .into_iter()
+                    .filter(|item| variant_required.contains(item))
+                    .collect(),
+            });
+        }
+
+        map.insert("properties".to_string(), Value::Object(properties));
+        if let Some(required) = required.filter(|r| !r.is_empty()) {
+            map.insert("required".to_string(), Value::Array(required));
+        }
+    }
+
+    map.insert("type".to_string(), Value::String("object".to_string()));
+    map.entry("properties")
+        .or_insert_with(|| Value::Object(Map::new()));
+    Value::Object(map)
+}
+
+/// Down-level a schema to the OpenAPI 3.0 subset accepted by Gemini
+pub fn to_gemini(schema: &Value) -> Value {
+    let Value::Object(source) = schema else {
+        return schema.clone();
+    };
+
+    let mut map = source.clone();
+
+    // `allOf` has no equivalent, so its variants are merged into the schema itself
+    if let Some(Value::Array(variants)) = map.remove("allOf") {
+        for variant in variants {
+            if let Value::Object(variant) = variant {
+                merge_schema(&mut map, variant);
+            }
+        }
+    }
+
+    // `oneOf` is the closest thing to `anyOf` that Gemini understands
+    if let Some(one_of) = map.remove("oneOf") {
+        map.entry("anyOf").or_insert(one_of);
+    }
+
+    // `const` becomes a single-valued enum
+    if let Some(constant) = map.remove("const") {
+        map.entry("enum")
+            .or_insert_with(|| Value::Array(vec![constant]));
+    }
+
+    // Type unions are only supported as a single type plus `nullable`
+    if let Some(Value::Array(types)) = map.get("type").cloned() {
+        let mut non_null = types.iter().filter(|t| t.as_str() != Some("null"));
+        match non_null.next() {
+            Some(first) => {
+                map.insert("type".to_string(), first.clone());
+            }
+            None => {
+                map.remove("type");
+            }
+        }
+        if types.iter().any(|t| t.as_str() == Some("null")) {
+            map.insert("nullable".to_string(), Value::Bool(true));
+        }
+    }
+
+    // Enum values must be strings
+    if let Some(Value::Array(values)) = map.get("enum") {
+        let values = values
+            .iter()
+            .filter(|v| !v.is_null())
+            .map(|v| match v {
+                Value::String(s) => Value::String(s.clone()),
+                other => Value::String(other.to_string()),
+            })
+            .collect();
+        map.insert("enum".to_string(), Value::Array(values));
+        map.insert("type".to_string(), Value::String("string".to_string()));
+    }
+
+    // Only a handful of formats are accepted, anything else is rejected outright
+    let type_name = map.get("type").and_then(Value::as_str).unwrap_or_default();
+    let format_supported = match map.get("format").and_then(Value::as_str) {
+        Some(format) => matches!(
+            (type_name, format),
+            ("string", "enum" | "date-time")
+                | ("integer", "int32" | "int64")
+                | ("number", "float" | "double")
+        ),
+        None => true,
+    };
+    if !format_supported {
+        map.remove("format");
+    }
+
+    let mut result = Map::new();
+    for (key, value) in map {
+        if !GEMINI_KEYWORDS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match key.as_str() {
+            "properties" => match value {
+                Value::Object(properties) => Value::Object(
+                    properties
+                        .iter()
+                        .map(|(name, property)| (name.clone(), to_gemini(property)))
+                        .collect(),
+                ),
+                other => other,
+            },
+            // Tuple validation is not supported, so the first item schema is used for all items
+            "items" => match value {
+                Value::Array(items) => items.first().map(to_gemini).unwrap_or_default(),
+                other => to_gemini(&other),
+            },
+            "anyOf" => match value {
+                Value::Array(variants) => Value::Array(variants.iter().map(to_gemini).collect()),
+                other => other,
+            },
+            _ => value,
+        };
+        if !value.is_null() {
+            result.insert(key, value);
+        }
+    }
+    Value::Object(result)
+}
+
+/// Merge the keywords of one schema into another, combining properties and required lists
+fn merge_schema(target: &mut Map<String, Value>, source: Map<String, Value>) {
+    for (key, value) in source {
+        match (key.as_str(), target.get_mut(&key), value) {
+            ("properties", Some(Value::Object(existing)), Value::Object(incoming)) => {
+                for (name, property) in incoming {
+                    existing.entry(name).or_insert(property);
+                }
+            }
+            ("required", Some(Value::Array(existing)), Value::Array(incoming)) => {
+                for item in incoming {
+                    if !existing.contains(&item) {
+                        existing.push(item);
+                    }
+                }
+            }
+            (_, Some(_), _) => {}
+            (_, None, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
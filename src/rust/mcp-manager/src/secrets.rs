@@ -0,0 +1,152 @@
+use crate::error::{Error, Result};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+lazy_static! {
+    /// Reference to a secret inside an env value, e.g. `${secret:github-token}`
+    static ref SECRET_REFERENCE: Regex = Regex::new(r"\$\{(env|file|secret):([^}]+)\}").unwrap();
+}
+
+/// Source of the values behind `${secret:key}` references
+pub trait SecretProvider: Send + Sync {
+    /// Short name used in error messages
+    fn name(&self) -> &str;
+
+    /// Look up the value of a secret
+    fn get(&self, key: &str) -> BoxFuture<'static, Result<String>>;
+}
+
+/// Secrets stored in a JSON file mapping keys to values
+///
+/// The file is read on every lookup, so changed secrets apply to the next server start.
+pub struct FileSecretProvider {
+    path: PathBuf,
+}
+
+impl FileSecretProvider {
+    /// Create a provider reading the given file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SecretProvider for FileSecretProvider {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn get(&self, key: &str) -> BoxFuture<'static, Result<String>> {
+        let path = self.path.clone();
+        let key = key.to_string();
+        async move {
+            let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                Error::ConfigError(format!(
+                    "Failed to read secrets file '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            // Parse errors are not shown, as serde_json quotes the offending content
+            let secrets: HashMap<String, String> = serde_json::from_str(&content).map_err(|_| {
+                Error::ConfigError(format!(
+                    "Secrets file '{}' must be a JSON object of strings",
+                    path.display()
+                ))
+            })?;
+            secrets.get(&key).cloned().ok_or_else(|| {
+                Error::ConfigError(format!(
+                    "Secret '{}' is not defined in '{}'",
+                    key,
+                    path.display()
+                ))
+            })
+        }
+        .boxed()
+    }
+}
+
+/// Replace the `${env:NAME}`, `${file:/path}` and `${secret:key}` references in env values
+///
+/// Returns the resolved environment and the values the references resolved to.
+pub async fn resolve_env(
+    env: &HashMap<String, String>,
+    provider: Option<Arc<dyn SecretProvider>>,
+) -> Result<(HashMap<String, String>, Vec<String>)> {
+    let mut resolved = HashMap::with_capacity(env.len());
+    let mut secrets = Vec::new();
+    for (name, value) in env {
+        let mut values = HashMap::new();
+        for reference in SECRET_REFERENCE.captures_iter(value) {
+            let key = (reference[1].to_string(), reference[2].to_string());
+            if values.contains_key(&key) {
+                continue;
+            }
+            let secret = resolve_reference(&key.0, &key.1, provider.as_deref())
+                .await
+                .map_err(|e| match e {
+                    Error::ConfigError(message) => Error::ConfigError(format!(
+                        "Failed to resolve env variable '{}': {}",
+                        name, message
+                    )),
+                    e => e,
+                })?;
+            values.insert(key, secret);
+        }
+        let value = SECRET_REFERENCE.replace_all(value, |reference: &Captures| {
+            values[&(reference[1].to_string(), reference[2].to_string())].clone()
+        });
+        secrets.extend(values.into_values());
+        resolved.insert(name.clone(), value.into_owned());
+    }
+    Ok((resolved, secrets))
+}
+
+/// Apply `mask` to the parts of an env value outside its references, which hold no secret
+pub fn mask_literals(value: &str, mask: impl Fn(&str) -> String) -> String {
+    let mut masked = String::with_capacity(value.len());
+    let mut last = 0;
+    for reference in SECRET_REFERENCE.find_iter(value) {
+        masked.push_str(&mask(&value[last..reference.start()]));
+        masked.push_str(reference.as_str());
+        last = reference.end();
+    }
+    masked.push_str(&mask(&value[last..]));
+    masked
+}
+
+/// Look up the value behind one reference
+async fn resolve_reference(
+    kind: &str,
+    key: &str,
+    provider: Option<&dyn SecretProvider>,
+) -> Result<String> {
+    match kind {
+        "env" => std::env::var(key).map_err(|_| {
+            Error::ConfigError(format!("environment variable '{}' is not set", key))
+        }),
+        "file" => tokio::fs::read_to_string(key)
+            .await
+            // A trailing newline is almost never part of the secret
+            .map(|content| content.trim_end_matches(['\n', '\r']).to_string())
+            .map_err(|e| Error::ConfigError(format!("failed to read '{}': {}", key, e))),
+        _ => match provider {
+            Some(provider) => provider.get(key).await.map_err(|e| {
+                Error::ConfigError(format!(
+                    "{} secret provider failed for '{}': {}",
+                    provider.name(),
+                    key,
+                    e
+                ))
+            }),
+            None => Err(Error::ConfigError(format!(
+                "no secret provider is set to resolve '{}'",
+                key
+            ))),
+        },
+    }
+}
@@ -0,0 +1,16 @@
+use tokio::task::JoinHandle;
+
+/// Background task that is aborted when dropped
+pub struct AbortOnDrop(JoinHandle<()>);
+
+impl AbortOnDrop {
+    pub fn new(task: JoinHandle<()>) -> Self {
+        Self(task)
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::{Error, Result};
+use crate::models::ServerConfig;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+
+/// Visibility and naming rules for the tools of a single server
+///
+/// Patterns and aliases always refer to the names the server itself reports.
+pub struct ToolFilter {
+    /// Tools to expose; `None` exposes every tool
+    include: Option<GlobSet>,
+    /// Tools to hide, even if they are included
+    exclude: GlobSet,
+    /// Map of original tool name to the name it is exposed as
+    aliases: HashMap<String, String>,
+}
+
+impl ToolFilter {
+    /// Build the filter described by a server configuration
+    pub fn from_config(config: &ServerConfig) -> Result<Self> {
+        let include = match &config.include_tools {
+            Some(patterns) => Some(build_glob_set(&config.id, patterns)?),
+            None => None,
+        };
+        let exclude = build_glob_set(
+            &config.id,
+            config.exclude_tools.as_deref().unwrap_or_default(),
+        )?;
+        let aliases = config.aliases.clone().unwrap_or_default();
+
+        // Two tools exposed under the same name could not be told apart
+        let mut targets = HashMap::new();
+        for (original, alias) in &aliases {
+            if let Some(other) = targets.insert(alias.as_str(), original.as_str()) {
+                return Err(Error::ConfigError(format!(
+                    "Server '{}' aliases both '{}' and '{}' to '{}'",
+                    config.id, other, original, alias
+                )));
+            }
+        }
+
+        Ok(Self {
+            include,
+            exclude,
+            aliases,
+        })
+    }
+
+    /// Check whether a tool may be listed and called
+    pub fn allows(&self, tool_name: &str) -> bool {
+        let included = self
+            .include
+            .as_ref()
+            .is_none_or(|include| include.is_match(tool_name));
+        included && !self.exclude.is_match(tool_name)
+    }
+
+    /// Check that no alias takes the name another exposed tool keeps
+    ///
+    /// Which names are exposed depends on the tools the server offers, so this
+    /// runs once they are listed.
+    pub fn check_aliases(&self, server_id: &str, tool_names: &[&str]) -> Result<()> {
+        let exposed = |name: &str| tool_names.contains(&name) && self.allows(name);
+        for (original, alias) in &self.aliases {
+            if alias != original
+                && exposed(original)
+                && exposed(alias)
+                && !self.aliases.contains_key(alias)
+            {
+                return Err(Error::ConfigError(format!(
+                    "Server '{}' aliases '{}' to '{}', which is the name of another tool",
+                    server_id, original, alias
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the name a tool is exposed as
+    pub fn exposed_name(&self, tool_name: &str) -> String {
+        self.aliases
+            .get(tool_name)
+            .cloned()
+            .unwrap_or_else(|| tool_name.to_string())
+    }
+}
+
+/// Compile a list of glob patterns into a single matcher
+fn build_glob_set(server_id: &str, patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            Error::ConfigError(format!(
+                "Invalid tool pattern '{}' for server '{}': {}",
+                pattern, server_id, e
+            ))
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| {
+        Error::ConfigError(format!(
+            "Invalid tool patterns for server '{}': {}",
+            server_id, e
+        ))
+    })
+}
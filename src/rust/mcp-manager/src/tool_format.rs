@@ -0,0 +1,144 @@
+use crate::hash::fnv1a;
+use crate::models::{ProviderTool, ToolFormat, ToolInfo};
+use crate::schema;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+
+/// Longest function name accepted by all supported providers
+pub const MAX_TOOL_NAME_LENGTH: usize = 64;
+
+/// Length of the hash suffix used to disambiguate mangled names
+const HASH_SUFFIX_LENGTH: usize = 8;
+
+/// Build provider-safe names for a set of tools
+///
+/// Names are the tools' qualified names, restricted to `[A-Za-z0-9_-]` and
+/// at most [`MAX_TOOL_NAME_LENGTH`] characters. Names that had to be shortened or
+/// that would collide after sanitizing get a stable hash suffix, so the result
+/// only depends on the set of tools and not on their order. Should a suffixed
+/// name still collide, it also gets a counter.
+pub fn mangle_names(tools: &[ToolInfo]) -> Vec<String> {
+    let bases: Vec<String> = tools
+        .iter()
+        .map(|tool| sanitize(&tool.qualified_name))
+        .collect();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for base in &bases {
+        *counts.entry(base.as_str()).or_default() += 1;
+    }
+
+    let mut names: Vec<String> = tools
+        .iter()
+        .zip(&bases)
+        .map(|(tool, base)| {
+            if base.len() <= MAX_TOOL_NAME_LENGTH && counts[base.as_str()] == 1 {
+                return base.clone();
+            }
+            let hash = fnv1a(format!("{}\0{}", tool.server_id, tool.tool_name).as_bytes());
+            let prefix_length = MAX_TOOL_NAME_LENGTH - HASH_SUFFIX_LENGTH - 1;
+            let prefix: String = base.chars().take(prefix_length).collect();
+            format!("{}_{:08x}", prefix, hash)
+        })
+        .collect();
+
+    // A suffixed name may equal another tool's plain name or another suffixed name.
+    // Plain names are kept; the other names are settled by server ID and tool name.
+    let mut order: Vec<usize> = (0..tools.len()).collect();
+    order.sort_by_key(|&i| (names[i] != bases[i], &tools[i].server_id, &tools[i].tool_name));
+    let mut taken = HashSet::new();
+    for i in order {
+        let stem = names[i].clone();
+        let mut counter = 1;
+        while !taken.insert(names[i].clone()) {
+            counter += 1;
+            let suffix = format!("_{}", counter);
+            let length = stem.len().min(MAX_TOOL_NAME_LENGTH - suffix.len());
+            names[i] = format!("{}{}", &stem[..length], suffix);
+        }
+    }
+    names
+}
+
+/// Convert tools into definitions for the given provider
+pub fn export_tools(tools: &[ToolInfo], format: ToolFormat) -> Vec<ProviderTool> {
+    let names = mangle_names(tools);
+    tools
+        .iter()
+        .zip(names)
+        .map(|(tool, name)| {
+            let definition = definition(tool, &name, format);
+            ProviderTool {
+                name,
+                server_id: tool.server_id.clone(),
+                tool_name: tool.tool_name.clone(),
+                definition: serde_json::to_string(&definition).unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Find the tool a mangled name refers to
+pub fn resolve_name(tools: &[ToolInfo], name: &str) -> Option<ToolInfo> {
+    mangle_names(tools)
+        .iter()
+        .position(|mangled| mangled == name)
+        .map(|index| tools[index].clone())
+}
+
+/// Build a single provider tool definition
+fn definition(tool: &ToolInfo, name: &str, format: ToolFormat) -> Value {
+    let parameters = parameters(tool, format);
+    match format {
+        ToolFormat::OpenAI => json!({
+            "type": "function",
+            "function": {
+                "name": name,
+                "description": tool.description,
+                "parameters": parameters,
+            }
+        }),
+        ToolFormat::Anthropic => json!({
+            "name": name,
+            "description": tool.description,
+            "input_schema": parameters,
+        }),
+        ToolFormat::Gemini => json!({
+            "name": name,
+            "description": tool.description,
+            "parameters": parameters,
+        }),
+    }
+}
+
+/// Down-level a tool's input schema for the given provider
+fn parameters(tool: &ToolInfo, format: ToolFormat) -> Value {
+    let raw: Value = serde_json::from_str(&tool.parameters).unwrap_or_else(|_| json!({}));
+    let mut parameters = schema::inline_refs(&raw);
+    schema::strip_meta(&mut parameters);
+    let parameters = schema::normalize_root(parameters);
+    match format {
+        ToolFormat::OpenAI | ToolFormat::Anthropic => parameters,
+        ToolFormat::Gemini => schema::to_gemini(&parameters),
+    }
+}
+
+/// Replace characters providers reject in function names
+fn sanitize(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    // Gemini requires names to start with a letter or an underscore
+    if !sanitized.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
@@ -0,0 +1,325 @@
+use crate::log_files::format_timestamp;
+use crate::models::{
+    Direction, MessageKind, TraceEntry, TraceFormat, TraceQuery, now_millis,
+};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Number of messages kept per traced server when not configured
+pub const DEFAULT_TRACE_BUFFER_SIZE: usize = 1000;
+
+/// Request waiting for its response
+struct PendingRequest {
+    method: String,
+    started: Instant,
+}
+
+/// Captured messages and open requests of one server
+#[derive(Default)]
+struct ServerTrace {
+    /// Recent messages, oldest first
+    entries: VecDeque<TraceEntry>,
+    /// Open requests by whether they were sent by the manager and their ID
+    pending: HashMap<(bool, String), PendingRequest>,
+}
+
+/// Bounded per-server capture of JSON-RPC traffic
+///
+/// Responses are matched to their requests by ID, so each response records
+/// the method it answers and how long the answer took.
+pub struct Tracer {
+    /// Traces by server ID
+    traces: Mutex<HashMap<String, ServerTrace>>,
+    /// Number of messages kept per server
+    capacity: usize,
+}
+
+impl Tracer {
+    /// Create a tracer keeping up to `capacity` messages per server
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            traces: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Forget the open requests of a server, e.g. because it is starting again
+    pub fn reset_pending(&self, server_id: &str) {
+        if let Some(trace) = self.traces.lock().unwrap().get_mut(server_id) {
+            trace.pending.clear();
+        }
+    }
+
+    /// Capture a message, returning an entry for each message it contains
+    ///
+    /// The entries are kept once they are numbered and passed to `store`.
+    pub fn record(&self, server_id: &str, direction: Direction, message: &Value) -> Vec<TraceEntry> {
+        let mut traces = self.traces.lock().unwrap();
+        let trace = traces.entry(server_id.to_string()).or_default();
+
+        // Batches are traced message by message
+        match message {
+            Value::Array(messages) => messages
+                .iter()
+                .map(|message| trace.capture(server_id, direction, message))
+                .collect(),
+            message => vec![trace.capture(server_id, direction, message)],
+        }
+    }
+
+    /// Keep an entry, dropping the server's oldest ones when full
+    pub fn store(&self, entry: &TraceEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut traces = self.traces.lock().unwrap();
+        let trace = traces.entry(entry.server_id.clone()).or_default();
+        while trace.entries.len() >= self.capacity {
+            trace.entries.pop_front();
+        }
+        trace.entries.push_back(entry.clone());
+    }
+
+    /// Get a server's captured messages matching a query, oldest first
+    pub fn query(&self, server_id: &str, query: &TraceQuery) -> Vec<TraceEntry> {
+        let traces = self.traces.lock().unwrap();
+        let Some(trace) = traces.get(server_id) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<TraceEntry> = trace
+            .entries
+            .iter()
+            .filter(|entry| {
+                query
+                    .since_sequence
+                    .is_none_or(|since| entry.sequence > since)
+            })
+            .filter(|entry| {
+                query.methods.as_ref().is_none_or(|methods| {
+                    entry
+                        .method
+                        .as_ref()
+                        .is_some_and(|method| methods.contains(method))
+                })
+            })
+            .cloned()
+            .collect();
+
+        if let Some(limit) = query.limit {
+            let excess = entries.len().saturating_sub(limit as usize);
+            entries.drain(..excess);
+        }
+        entries
+    }
+
+    /// Drop everything captured for a server
+    pub fn clear(&self, server_id: &str) {
+        self.traces.lock().unwrap().remove(server_id);
+    }
+
+    /// Render a server's captured messages in an export format
+    pub fn export(&self, server_id: &str, format: TraceFormat) -> Value {
+        let entries = self.query(server_id, &TraceQuery::default());
+        let exchanges = pair_exchanges(&entries);
+        match format {
+            TraceFormat::Har => to_har(server_id, &exchanges),
+            TraceFormat::Inspector => to_inspector(&exchanges),
+        }
+    }
+}
+
+impl ServerTrace {
+    /// Classify a single message and match it against open requests
+    fn capture(&mut self, server_id: &str, direction: Direction, message: &Value) -> TraceEntry {
+        let id = message.get("id").map(Value::to_string);
+        let mut method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let outgoing = direction == Direction::Outgoing;
+        let mut latency_ms = None;
+
+        let kind = match (&method, &id) {
+            (Some(method), Some(id)) => {
+                self.pending.insert(
+                    (outgoing, id.clone()),
+                    PendingRequest {
+                        method: method.clone(),
+                        started: Instant::now(),
+                    },
+                );
+                MessageKind::Request
+            }
+            (Some(method), None) => {
+                // A cancelled request will never be answered
+                if method == "notifications/cancelled" {
+                    if let Some(request_id) = message.pointer("/params/requestId") {
+                        self.pending.remove(&(outgoing, request_id.to_string()));
+                    }
+                }
+                MessageKind::Notification
+            }
+            (None, _) => {
+                // Responses travel the opposite way of their request
+                if let Some(request) = id
+                    .as_ref()
+                    .and_then(|id| self.pending.remove(&(!outgoing, id.clone())))
+                {
+                    method = Some(request.method);
+                    latency_ms = Some(request.started.elapsed().as_secs_f64() * 1000.0);
+                }
+                if message.get("error").is_some() {
+                    MessageKind::Error
+                } else {
+                    MessageKind::Response
+                }
+            }
+        };
+
+        TraceEntry {
+            server_id: server_id.to_string(),
+            direction,
+            kind,
+            method,
+            id,
+            message: message.to_string(),
+            timestamp: now_millis(),
+            latency_ms,
+            sequence: 0,
+        }
+    }
+}
+
+/// A request with its response, or a lone notification
+struct Exchange<'a> {
+    request: &'a TraceEntry,
+    response: Option<&'a TraceEntry>,
+}
+
+/// Pair captured requests with their responses, in request order
+fn pair_exchanges(entries: &[TraceEntry]) -> Vec<Exchange<'_>> {
+    let mut exchanges: Vec<Exchange> = Vec::new();
+    let mut open: HashMap<(bool, String), usize> = HashMap::new();
+
+    for entry in entries {
+        let outgoing = entry.direction == Direction::Outgoing;
+        match (entry.kind, &entry.id) {
+            (MessageKind::Request, Some(id)) => {
+                open.insert((outgoing, id.clone()), exchanges.len());
+                exchanges.push(Exchange {
+                    request: entry,
+                    response: None,
+                });
+            }
+            (MessageKind::Response | MessageKind::Error, Some(id)) => {
+                if let Some(index) = open.remove(&(!outgoing, id.clone())) {
+                    exchanges[index].response = Some(entry);
+                }
+            }
+            (MessageKind::Notification, _) => exchanges.push(Exchange {
+                request: entry,
+                response: None,
+            }),
+            // Responses whose request was already dropped from the buffer
+            _ => {}
+        }
+    }
+    exchanges
+}
+
+/// Render exchanges as an HTTP Archive, one entry per request
+fn to_har(server_id: &str, exchanges: &[Exchange]) -> Value {
+    let entries: Vec<Value> = exchanges
+        .iter()
+        .map(|exchange| {
+            let request = exchange.request;
+            let method = request.method.as_deref().unwrap_or_default();
+            let latency = exchange
+                .response
+                .and_then(|response| response.latency_ms)
+                .unwrap_or(0.0);
+            let (status, status_text, content) = match exchange.response {
+                Some(response) if response.kind == MessageKind::Error => {
+                    (500, "Error", response.message.as_str())
+                }
+                Some(response) => (200, "OK", response.message.as_str()),
+                None => (0, "", ""),
+            };
+            json!({
+                "startedDateTime": format_timestamp(request.timestamp),
+                "time": latency,
+                "request": {
+                    "method": "POST",
+                    "url": format!("mcp://{}/{}", server_id, method),
+                    "httpVersion": "JSON-RPC/2.0",
+                    "cookies": [],
+                    "headers": [],
+                    "queryString": [],
+                    "postData": {
+                        "mimeType": "application/json",
+                        "text": request.message,
+                    },
+                    "headersSize": -1,
+                    "bodySize": request.message.len(),
+                },
+                "response": {
+                    "status": status,
+                    "statusText": status_text,
+                    "httpVersion": "JSON-RPC/2.0",
+                    "cookies": [],
+                    "headers": [],
+                    "content": {
+                        "size": content.len(),
+                        "mimeType": "application/json",
+                        "text": content,
+                    },
+                    "redirectURL": "",
+                    "headersSize": -1,
+                    "bodySize": content.len(),
+                },
+                "cache": {},
+                "timings": { "send": 0, "wait": latency, "receive": 0 },
+                "comment": match request.direction {
+                    Direction::Outgoing => "sent by the manager",
+                    Direction::Incoming => "sent by the server",
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "mcp-manager", "version": env!("CARGO_PKG_VERSION") },
+            "entries": entries,
+        }
+    })
+}
+
+/// Render exchanges as the MCP Inspector's request history and server notifications
+fn to_inspector(exchanges: &[Exchange]) -> Value {
+    let mut request_history = Vec::new();
+    let mut server_notifications = Vec::new();
+
+    for exchange in exchanges {
+        let request = exchange.request;
+        if request.kind == MessageKind::Notification && request.direction == Direction::Incoming {
+            server_notifications
+                .push(serde_json::from_str::<Value>(&request.message).unwrap_or_default());
+            continue;
+        }
+        let mut item = json!({ "request": request.message });
+        if let Some(response) = exchange.response {
+            item["response"] = Value::String(response.message.clone());
+        }
+        request_history.push(item);
+    }
+
+    json!({
+        "requestHistory": request_history,
+        "serverNotifications": server_notifications,
+    })
+}
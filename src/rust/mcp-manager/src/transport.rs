@@ -0,0 +1,113 @@
+use crate::models::Direction;
+use rmcp::service::{RoleClient, RxJsonRpcMessage, TxJsonRpcMessage};
+use rmcp::transport::Transport;
+use serde_json::Value;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// Callback seeing every JSON-RPC message exchanged with a server
+pub type MessageObserver = Arc<dyn Fn(Direction, &Value) + Send + Sync>;
+
+/// Transport wrapper that reports every message to an observer
+pub struct ObservedTransport<T> {
+    inner: T,
+    observer: MessageObserver,
+}
+
+impl<T> ObservedTransport<T> {
+    /// Wrap a transport
+    pub fn new(inner: T, observer: MessageObserver) -> Self {
+        Self { inner, observer }
+    }
+}
+
+impl<T> Transport<RoleClient> for ObservedTransport<T>
+where
+    T: Transport<RoleClient>,
+{
+    type Error = T::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        if let Ok(message) = serde_json::to_value(&item) {
+            (self.observer)(Direction::Outgoing, &message);
+        }
+        self.inner.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleClient>> {
+        let item = self.inner.receive().await;
+        if let Some(message) = item.as_ref().and_then(|item| serde_json::to_value(item).ok()) {
+            (self.observer)(Direction::Incoming, &message);
+        }
+        item
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
+
+/// Writer whose stream can be closed by an `InputCloser` while a transport owns it
+pub struct ClosableWriter<W> {
+    inner: Arc<Mutex<Option<W>>>,
+}
+
+/// Closes the stream of a `ClosableWriter`, so the process reading it sees end of input
+#[derive(Clone)]
+pub struct InputCloser(Arc<dyn Fn() + Send + Sync>);
+
+impl<W: Send + 'static> ClosableWriter<W> {
+    /// Wrap a writer, returning it with the handle closing it
+    pub fn new(inner: W) -> (Self, InputCloser) {
+        let inner = Arc::new(Mutex::new(Some(inner)));
+        let shared = inner.clone();
+        let closer = InputCloser(Arc::new(move || {
+            shared.lock().unwrap().take();
+        }));
+        (Self { inner }, closer)
+    }
+
+    fn poll_with<T>(
+        &self,
+        poll: impl FnOnce(Pin<&mut W>) -> Poll<io::Result<T>>,
+    ) -> Poll<io::Result<T>>
+    where
+        W: Unpin,
+    {
+        match self.inner.lock().unwrap().as_mut() {
+            Some(inner) => poll(Pin::new(inner)),
+            None => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+        }
+    }
+}
+
+impl<W: AsyncWrite + Send + Unpin + 'static> AsyncWrite for ClosableWriter<W> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_with(|inner| inner.poll_write(cx, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_with(|inner| inner.poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.inner.lock().unwrap().as_mut() {
+            Some(inner) => Pin::new(inner).poll_shutdown(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl InputCloser {
+    /// Close the stream; later writes fail
+    pub fn close(&self) {
+        (self.0)()
+    }
+}
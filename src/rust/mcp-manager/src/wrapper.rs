@@ -1,10 +1,111 @@
 
+use crate::approval::ApprovalHandler;
+use crate::error::{Error, NapiError, NapiResult, Result};
+use crate::interceptor::{ArgumentDefaults, ResultTruncation, ToolCall, ToolInterceptor};
 use crate::manager::McpManager;
-use crate::models::{McpEvent, ServerConfig, ToolExecutionRequest, ToolInfo};
+use crate::secrets::{FileSecretProvider, SecretProvider};
+use crate::models::{
+    ApplyOptions, ApplyResult, ApprovalDecision, ApprovalPolicy, ApprovalRequest,
+    ConfigWatchOptions, EventFilter, EventQuery, InterceptedCall, InterceptedResult, LimitStatus,
+    ManagerOptions, McpEvent, NamespacePolicy, PromptInfo, ProviderTool, ResourceInfo,
+    ServerConfig, ServerInfo, ServerStats, StatsWatchOptions, ToolExecutionRequest, ToolFormat,
+    ToolInfo, TraceEntry, TraceFormat, TraceQuery,
+};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use napi::bindgen_prelude::Promise;
 use napi::threadsafe_function::ThreadsafeFunction;
 use napi_derive::napi;
 use std::sync::Arc;
 
+/// Interceptor whose hooks are JavaScript callbacks resolving to JSON strings
+struct JsInterceptor {
+    /// Callback resolving to the arguments to call the tool with
+    before: Option<ThreadsafeFunction<InterceptedCall>>,
+    /// Callback resolving to the result to return
+    after: Option<ThreadsafeFunction<InterceptedResult>>,
+}
+
+impl ToolInterceptor for JsInterceptor {
+    fn name(&self) -> &str {
+        "javascript"
+    }
+
+    fn before_call(&self, mut call: ToolCall) -> BoxFuture<'static, Result<ToolCall>> {
+        let Some(before) = self.before.clone() else {
+            return futures::future::ready(Ok(call)).boxed();
+        };
+        async move {
+            let arguments = call_js(
+                &before,
+                InterceptedCall {
+                    server_id: call.server_id.clone(),
+                    tool_name: call.tool_name.clone(),
+                    arguments: serde_json::Value::Object(call.arguments.clone()).to_string(),
+                },
+            )
+            .await?;
+            call.arguments = match serde_json::from_str(&arguments) {
+                Ok(serde_json::Value::Object(arguments)) => arguments,
+                _ => {
+                    return Err(Error::Other(
+                        "Interceptor must resolve to a JSON object of arguments".to_string(),
+                    ))
+                }
+            };
+            Ok(call)
+        }
+        .boxed()
+    }
+
+    fn after_call(
+        &self,
+        call: &ToolCall,
+        result: serde_json::Value,
+    ) -> BoxFuture<'static, Result<serde_json::Value>> {
+        let Some(after) = self.after.clone() else {
+            return futures::future::ready(Ok(result)).boxed();
+        };
+        let intercepted = InterceptedResult {
+            server_id: call.server_id.clone(),
+            tool_name: call.tool_name.clone(),
+            arguments: serde_json::Value::Object(call.arguments.clone()).to_string(),
+            result: result.to_string(),
+        };
+        async move {
+            let result = call_js(&after, intercepted).await?;
+            serde_json::from_str(&result).map_err(|e| {
+                Error::Other(format!("Interceptor must resolve to a JSON result: {}", e))
+            })
+        }
+        .boxed()
+    }
+}
+
+/// Call a JavaScript callback and wait for the promise it returns
+async fn call_js<T: 'static>(callback: &ThreadsafeFunction<T>, value: T) -> Result<String> {
+    let promise = callback
+        .call_async::<Promise<String>>(Ok(value))
+        .await
+        .map_err(|e| Error::Other(e.to_string()))?;
+    promise.await.map_err(|e| Error::Other(e.to_string()))
+}
+
+/// Secret provider whose lookups are a JavaScript callback resolving to the value
+struct JsSecretProvider(ThreadsafeFunction<String>);
+
+impl SecretProvider for JsSecretProvider {
+    fn name(&self) -> &str {
+        "javascript"
+    }
+
+    fn get(&self, key: &str) -> BoxFuture<'static, Result<String>> {
+        let callback = self.0.clone();
+        let key = key.to_string();
+        async move { call_js(&callback, key).await }.boxed()
+    }
+}
+
 /// NAPI wrapper for the MCP manager
 #[napi(js_name = "McpManager")]
 pub struct McpManagerWrapper {
@@ -16,27 +117,179 @@ pub struct McpManagerWrapper {
 impl McpManagerWrapper {
     /// Create a new MCP manager wrapper
     #[napi(constructor)]
-    pub fn new(event_callback: ThreadsafeFunction<McpEvent>) -> Self {
-        let manager = McpManager::new(event_callback);
-        Self {
-            manager: Arc::new(manager),
-        }
+    pub fn new(
+        event_callback: ThreadsafeFunction<McpEvent>,
+        options: Option<ManagerOptions>,
+    ) -> std::result::Result<Self, NapiError> {
+        let manager =
+            McpManager::new_shared(Arc::new(event_callback), options.unwrap_or_default())?;
+        Ok(Self { manager })
     }
 
     /// Start a new MCP server
+    #[napi(ts_return_type = "Promise<void>")]
+    pub async fn start(&self, config: ServerConfig) -> NapiResult<()> {
+        self.manager.start(config).await.into()
+    }
+
+    /// Start the servers defined by a `claude_desktop_config.json`-style file, given as a path or JSON, and stop all others
+    #[napi(ts_return_type = "Promise<Array<ApplyResult>>")]
+    pub async fn start_from_config(&self, source: String) -> NapiResult<Vec<ApplyResult>> {
+        self.manager.start_from_config(&source).await.into()
+    }
+
+    /// Start, stop and restart servers so exactly the given ones run, reporting the outcome per server
+    #[napi(ts_return_type = "Promise<Array<ApplyResult>>")]
+    pub async fn apply(
+        &self,
+        configs: Vec<ServerConfig>,
+        options: Option<ApplyOptions>,
+    ) -> NapiResult<Vec<ApplyResult>> {
+        self.manager
+            .apply(configs, options.unwrap_or_default())
+            .await
+            .into()
+    }
+
+    /// Start the servers defined by a config file and reload it whenever it changes, emitting `configReloaded` events
+    #[napi(ts_return_type = "Promise<Array<ApplyResult>>")]
+    pub async fn watch_config(
+        &self,
+        path: String,
+        options: Option<ConfigWatchOptions>,
+    ) -> NapiResult<Vec<ApplyResult>> {
+        self.manager
+            .watch_config(&path, options.unwrap_or_default())
+            .await
+            .into()
+    }
+
+    /// Stop watching the config file passed to `watchConfig`; running servers are kept
     #[napi]
-    pub async fn start(&self, config: ServerConfig) -> napi::Result<()> {
-        self.manager.start(config).await.map_err(|e| {
-            napi::Error::new(napi::Status::GenericFailure, e.to_string())
-        })
+    pub fn unwatch_config(&self) {
+        self.manager.unwatch_config();
     }
 
     /// Stop an MCP server
+    #[napi(ts_return_type = "Promise<void>")]
+    pub async fn stop(&self, server_id: String) -> NapiResult<()> {
+        self.manager.stop(&server_id).await.into()
+    }
+
+    /// Set the minimum level of MCP log messages a server sends
+    ///
+    /// Accepts the MCP levels (`debug`, `info`, `notice`, `warning`, `error`,
+    /// `critical`, `alert`, `emergency`) as well as `warn`.
+    #[napi(ts_return_type = "Promise<void>")]
+    pub async fn set_log_level(&self, server_id: String, level: String) -> NapiResult<()> {
+        self.manager
+            .set_log_level(&server_id, &level)
+            .await
+            .into()
+    }
+
+    /// Get the last lines of a server's log file
+    #[napi]
+    pub fn get_log_tail(
+        &self,
+        server_id: String,
+        lines: u32,
+    ) -> std::result::Result<Vec<String>, NapiError> {
+        self.manager
+            .get_log_tail(&server_id, lines as usize)
+            .map_err(Into::into)
+    }
+
+    /// Get recently emitted events matching a query, oldest first
+    #[napi]
+    pub fn get_recent_events(&self, query: Option<EventQuery>) -> Vec<McpEvent> {
+        self.manager.get_recent_events(&query.unwrap_or_default())
+    }
+
+    /// Replace the event callback, replaying buffered events newer than `replaySince` first
+    #[napi]
+    pub fn set_event_callback(
+        &self,
+        callback: ThreadsafeFunction<McpEvent>,
+        replay_since: Option<i64>,
+    ) {
+        self.manager.set_event_callback(Arc::new(callback), replay_since);
+    }
+
+    /// Get the captured JSON-RPC traffic of a server started with `trace`, oldest first
+    #[napi]
+    pub fn get_trace(&self, server_id: String, query: Option<TraceQuery>) -> Vec<TraceEntry> {
+        self.manager.get_trace(&server_id, &query.unwrap_or_default())
+    }
+
+    /// Drop the captured JSON-RPC traffic of a server
+    #[napi]
+    pub fn clear_trace(&self, server_id: String) {
+        self.manager.clear_trace(&server_id);
+    }
+
+    /// Write the captured JSON-RPC traffic of a server to a JSON file
+    #[napi]
+    pub fn export_trace(
+        &self,
+        server_id: String,
+        path: String,
+        format: TraceFormat,
+    ) -> std::result::Result<(), NapiError> {
+        self.manager
+            .export_trace(&server_id, &path, format)
+            .map_err(Into::into)
+    }
+
+    /// Listen to the events passing a filter and return the subscription ID
+    ///
+    /// Buffered events newer than `replaySince` that pass the filter are
+    /// delivered first.
+    #[napi]
+    pub fn subscribe(
+        &self,
+        filter: Option<EventFilter>,
+        callback: ThreadsafeFunction<McpEvent>,
+        replay_since: Option<i64>,
+    ) -> u32 {
+        self.manager.subscribe(filter.unwrap_or_default(), Arc::new(callback), replay_since)
+    }
+
+    /// Remove an event subscription, returning whether it was registered
+    #[napi]
+    pub fn unsubscribe(&self, id: u32) -> bool {
+        self.manager.unsubscribe(id)
+    }
+
+    /// Get the running servers, in start order, with secret values in their env masked
+    #[napi]
+    pub fn list_servers(&self) -> Vec<ServerInfo> {
+        self.manager.list_servers()
+    }
+
+    /// Get the resource limits a server reached, also after a hard limit stopped it
+    #[napi]
+    pub fn get_limit_status(&self, server_id: String) -> Option<LimitStatus> {
+        self.manager.get_limit_status(&server_id)
+    }
+
+    /// Measure CPU, memory, threads, open files and child processes of a server's process tree
+    #[napi(ts_return_type = "Promise<ServerStats>")]
+    pub async fn get_server_stats(&self, server_id: String) -> NapiResult<ServerStats> {
+        self.manager.get_server_stats(&server_id).await.into()
+    }
+
+    /// Emit a `serverStats` event for every server at a fixed interval
     #[napi]
-    pub async fn stop(&self, server_id: String) -> napi::Result<()> {
-        self.manager.stop(&server_id).await.map_err(|e| {
-            napi::Error::new(napi::Status::GenericFailure, e.to_string())
-        })
+    pub async fn watch_stats(&self, options: Option<StatsWatchOptions>) {
+        // Async so the watch task is spawned on the runtime
+        self.manager.watch_stats(options.unwrap_or_default());
+    }
+
+    /// Stop the `serverStats` events started by `watchStats`
+    #[napi]
+    pub fn unwatch_stats(&self) {
+        self.manager.unwatch_stats();
     }
 
     /// Get all available tools across all servers
@@ -45,11 +298,201 @@ impl McpManagerWrapper {
         self.manager.get_tools()
     }
 
+    /// Set how tool names are made unique across servers
+    ///
+    /// Qualified names change with the policy, so every running server gets a
+    /// `toolsUpdated` event with its tools under the new names.
+    #[napi]
+    pub fn set_namespace_policy(&self, policy: NamespacePolicy) {
+        self.manager.set_namespace_policy(policy)
+    }
+
+    /// Get the current namespacing policy
+    #[napi]
+    pub fn get_namespace_policy(&self) -> NamespacePolicy {
+        self.manager.namespace_policy()
+    }
+
+    /// Get all available tools as definitions for an LLM provider
+    #[napi]
+    pub fn get_tools_as(&self, format: ToolFormat) -> Vec<ProviderTool> {
+        self.manager.get_tools_as(format)
+    }
+
+    /// Find the tool behind a provider tool name returned by `getToolsAs`
+    #[napi]
+    pub fn resolve_tool_name(&self, name: String) -> Option<ToolInfo> {
+        self.manager.resolve_tool_name(&name)
+    }
+
+    /// List the resources a server offers
+    #[napi(ts_return_type = "Promise<Array<ResourceInfo>>")]
+    pub async fn list_resources(&self, server_id: String) -> NapiResult<Vec<ResourceInfo>> {
+        self.manager.list_resources(&server_id).await.into()
+    }
+
+    /// List the prompts a server offers
+    #[napi(ts_return_type = "Promise<Array<PromptInfo>>")]
+    pub async fn list_prompts(&self, server_id: String) -> NapiResult<Vec<PromptInfo>> {
+        self.manager.list_prompts(&server_id).await.into()
+    }
+
     /// Execute a tool on an MCP server
+    #[napi(ts_return_type = "Promise<string>")]
+    pub async fn execute_tool(&self, request: ToolExecutionRequest) -> NapiResult<String> {
+        self.manager.execute_tool(request).await.into()
+    }
+
+    /// Execute a tool identified by its qualified name
+    #[napi(ts_return_type = "Promise<string>")]
+    pub async fn execute_tool_by_qualified_name(
+        &self,
+        name: String,
+        inputs: String,
+    ) -> NapiResult<String> {
+        self.manager
+            .execute_tool_by_qualified_name(&name, inputs)
+            .await
+            .into()
+    }
+
+    /// Register a callback asked before tools whose policy is `alwaysAsk` run
+    ///
+    /// The callback resolves to a decision that can approve the call, deny it,
+    /// or approve it with replacement arguments.
+    #[napi(
+        ts_args_type = "callback: (err: Error | null, arg: ApprovalRequest) => Promise<ApprovalDecision>"
+    )]
+    pub fn set_approval_handler(&self, callback: ThreadsafeFunction<ApprovalRequest>) {
+        let handler: ApprovalHandler = Arc::new(move |request| {
+            let callback = callback.clone();
+            async move {
+                let promise = callback
+                    .call_async::<Promise<ApprovalDecision>>(Ok(request))
+                    .await
+                    .map_err(|e| Error::Other(format!("Approval handler failed: {}", e)))?;
+                promise
+                    .await
+                    .map_err(|e| Error::Other(format!("Approval handler failed: {}", e)))
+            }
+            .boxed()
+        });
+        self.manager.set_approval_handler(Some(handler));
+    }
+
+    /// Resolve `${secret:key}` references in server env values with a callback returning a promise of the value
+    #[napi(ts_args_type = "callback: (err: Error | null, arg: string) => Promise<string>")]
+    pub fn set_secret_provider(&self, callback: ThreadsafeFunction<String>) {
+        self.manager
+            .set_secret_provider(Some(Arc::new(JsSecretProvider(callback))));
+    }
+
+    /// Resolve `${secret:key}` references from a JSON file mapping keys to values
+    #[napi]
+    pub fn set_secrets_file(&self, path: String) {
+        self.manager
+            .set_secret_provider(Some(Arc::new(FileSecretProvider::new(path))));
+    }
+
+    /// Remove the secret provider; servers with `${secret:key}` references fail to start
+    #[napi]
+    pub fn clear_secret_provider(&self) {
+        self.manager.set_secret_provider(None);
+    }
+
+    /// Remove the approval callback; calls to tools whose policy is `alwaysAsk` are denied until another is registered
+    #[napi]
+    pub fn clear_approval_handler(&self) {
+        self.manager.set_approval_handler(None);
+    }
+
+    /// Set whether calls to a tool need approval
+    #[napi]
+    pub fn set_tool_approval_policy(
+        &self,
+        server_id: String,
+        tool_name: String,
+        policy: ApprovalPolicy,
+    ) {
+        self.manager
+            .set_tool_approval_policy(&server_id, &tool_name, policy)
+    }
+
+    /// Remove a tool's approval policy so the default policy applies again
+    #[napi]
+    pub fn clear_tool_approval_policy(&self, server_id: String, tool_name: String) {
+        self.manager.clear_tool_approval_policy(&server_id, &tool_name)
+    }
+
+    /// Get the approval policy that applies to a tool
+    #[napi]
+    pub fn get_tool_approval_policy(&self, server_id: String, tool_name: String) -> ApprovalPolicy {
+        self.manager.tool_approval_policy(&server_id, &tool_name)
+    }
+
+    /// Set the approval policy for tools without a policy of their own
+    ///
+    /// Until it is set, such tools are asked about while an approval handler is
+    /// registered and run without asking otherwise.
+    #[napi]
+    pub fn set_default_approval_policy(&self, policy: ApprovalPolicy) {
+        self.manager.set_default_approval_policy(policy)
+    }
+
+    /// Add interceptor callbacks around every tool call and return the interceptor's ID
+    ///
+    /// `before` resolves to the arguments (as a JSON string) and `after` to the
+    /// result (as a JSON string). Interceptors run in the order they are added
+    /// before the call, and in reverse order after it.
+    #[napi(
+        ts_args_type = "before?: (err: Error | null, arg: InterceptedCall) => Promise<string>, after?: (err: Error | null, arg: InterceptedResult) => Promise<string>"
+    )]
+    pub fn add_interceptor(
+        &self,
+        before: Option<ThreadsafeFunction<InterceptedCall>>,
+        after: Option<ThreadsafeFunction<InterceptedResult>>,
+    ) -> u32 {
+        self.manager
+            .add_interceptor(Arc::new(JsInterceptor { before, after }))
+    }
+
+    /// Fill in missing arguments of tool calls and return the interceptor's ID
+    ///
+    /// Defaults are given as a JSON object string and can be limited to one
+    /// server and one tool.
+    #[napi]
+    pub fn add_argument_defaults(
+        &self,
+        defaults: String,
+        server_id: Option<String>,
+        tool_name: Option<String>,
+    ) -> std::result::Result<u32, NapiError> {
+        let defaults = match serde_json::from_str(&defaults) {
+            Ok(serde_json::Value::Object(defaults)) => defaults,
+            _ => {
+                return Err(
+                    Error::ConfigError("Defaults must be a JSON object".to_string()).into(),
+                )
+            }
+        };
+        Ok(self.manager.add_interceptor(Arc::new(ArgumentDefaults {
+            server_id,
+            tool_name,
+            defaults,
+        })))
+    }
+
+    /// Truncate text content of tool results and return the interceptor's ID
+    #[napi]
+    pub fn add_result_truncation(&self, max_length: u32) -> u32 {
+        self.manager.add_interceptor(Arc::new(ResultTruncation {
+            max_length: max_length as usize,
+        }))
+    }
+
+    /// Remove an interceptor, returning whether it was registered
     #[napi]
-    pub async fn execute_tool(&self, request: ToolExecutionRequest) -> napi::Result<String> {
-        self.manager.execute_tool(request).await.map_err(|e| {
-            napi::Error::new(napi::Status::GenericFailure, e.to_string())
-        })
+    pub fn remove_interceptor(&self, id: u32) -> bool {
+        self.manager.remove_interceptor(id)
     }
 }
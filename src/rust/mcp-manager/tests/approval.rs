@@ -0,0 +1,138 @@
+mod common;
+
+use common::{call, manager, mock_server, result_text};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use mcp_manager::{
+    ApprovalDecision, ApprovalHandler, ApprovalPolicy, Error, ServerConfig, ToolCall,
+    ToolInterceptor,
+};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// Server whose file tools echo their arguments
+fn echo_tools(id: &str) -> ServerConfig {
+    let script = json!({ "tools": [
+        { "name": "read_file" },
+        { "name": "write_file" },
+        { "name": "delete_file" },
+    ] });
+    mock_server(id, script)
+}
+
+#[tokio::test]
+async fn handler_approves_denies_and_rewrites_calls() {
+    let (manager, _events) = manager();
+    manager.start(echo_tools("files")).await.unwrap();
+
+    let asked = Arc::new(Mutex::new(Vec::new()));
+    let handler: ApprovalHandler = Arc::new({
+        let asked = asked.clone();
+        move |request| {
+            asked.lock().unwrap().push(request.tool_name.clone());
+            let decision = match request.tool_name.as_str() {
+                "delete_file" => ApprovalDecision {
+                    approved: false,
+                    arguments: None,
+                    reason: Some("too dangerous".to_string()),
+                },
+                _ => ApprovalDecision {
+                    approved: true,
+                    arguments: Some(r#"{"path":"/tmp/safe"}"#.to_string()),
+                    reason: None,
+                },
+            };
+            async move { Ok(decision) }.boxed()
+        }
+    });
+    manager.set_approval_handler(Some(handler));
+    manager.set_tool_approval_policy("files", "read_file", ApprovalPolicy::AlwaysAllow);
+    assert_eq!(
+        manager.tool_approval_policy("files", "write_file"),
+        ApprovalPolicy::AlwaysAsk
+    );
+
+    let inputs = r#"{"path":"/etc/passwd"}"#;
+    let result = manager.execute_tool(call("files", "read_file", inputs)).await.unwrap();
+    assert_eq!(result_text(&result), inputs);
+    let result = manager.execute_tool(call("files", "write_file", inputs)).await.unwrap();
+    assert_eq!(result_text(&result), r#"{"path":"/tmp/safe"}"#);
+    let result = manager.execute_tool(call("files", "delete_file", inputs)).await;
+    assert!(
+        matches!(&result, Err(Error::ApprovalDenied(message)) if message.contains("too dangerous")),
+        "{:?}",
+        result
+    );
+    assert_eq!(*asked.lock().unwrap(), ["write_file", "delete_file"]);
+
+    manager.set_tool_approval_policy("files", "write_file", ApprovalPolicy::Never);
+    let result = manager.execute_tool(call("files", "write_file", inputs)).await;
+    assert!(matches!(result, Err(Error::ApprovalDenied(_))));
+    assert_eq!(asked.lock().unwrap().len(), 2);
+
+    manager.stop("files").await.unwrap();
+}
+
+/// Records the arguments its after-call hook is given
+struct ArgumentRecorder(Arc<Mutex<Option<Value>>>);
+
+impl ToolInterceptor for ArgumentRecorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn after_call(&self, call: &ToolCall, result: Value) -> BoxFuture<'static, mcp_manager::Result<Value>> {
+        *self.0.lock().unwrap() = Some(Value::Object(call.arguments.clone()));
+        futures::future::ready(Ok(result)).boxed()
+    }
+}
+
+#[tokio::test]
+async fn after_hooks_see_the_approved_arguments() {
+    let (manager, _events) = manager();
+    manager.start(echo_tools("files")).await.unwrap();
+
+    let handler: ApprovalHandler = Arc::new(|_| {
+        let decision = ApprovalDecision {
+            approved: true,
+            arguments: Some(r#"{"path":"/tmp/safe"}"#.to_string()),
+            reason: None,
+        };
+        async move { Ok(decision) }.boxed()
+    });
+    manager.set_approval_handler(Some(handler));
+    manager.set_default_approval_policy(ApprovalPolicy::AlwaysAsk);
+    let recorded = Arc::new(Mutex::new(None));
+    manager.add_interceptor(Arc::new(ArgumentRecorder(recorded.clone())));
+
+    manager
+        .execute_tool(call("files", "write_file", r#"{"path":"/etc/passwd"}"#))
+        .await
+        .unwrap();
+    assert_eq!(*recorded.lock().unwrap(), Some(json!({ "path": "/tmp/safe" })));
+
+    manager.stop("files").await.unwrap();
+}
+
+#[tokio::test]
+async fn asking_without_a_handler_denies_the_call() {
+    let (manager, _events) = manager();
+    manager.start(echo_tools("files")).await.unwrap();
+
+    // Without any approval configured, tools run
+    assert_eq!(
+        manager.tool_approval_policy("files", "write_file"),
+        ApprovalPolicy::AlwaysAllow
+    );
+    manager.execute_tool(call("files", "write_file", "{}")).await.unwrap();
+
+    manager.set_tool_approval_policy("files", "delete_file", ApprovalPolicy::AlwaysAsk);
+    let result = manager.execute_tool(call("files", "delete_file", "{}")).await;
+    assert!(matches!(result, Err(Error::ApprovalDenied(_))), "{:?}", result);
+
+    manager.set_default_approval_policy(ApprovalPolicy::AlwaysAsk);
+    let result = manager.execute_tool(call("files", "write_file", "{}")).await;
+    assert!(matches!(result, Err(Error::ApprovalDenied(_))), "{:?}", result);
+
+    manager.stop("files").await.unwrap();
+}
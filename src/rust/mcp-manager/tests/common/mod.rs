@@ -0,0 +1,92 @@
+//! Helpers shared by the integration tests
+
+// Each test binary uses only some of the helpers
+#![allow(dead_code)]
+
+use mcp_manager::{
+    EventSink, ManagerOptions, McpEvent, McpManager, ServerConfig, ToolExecutionRequest,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How long to wait for an expected event before failing
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Events received from a manager
+pub struct Events(mpsc::UnboundedReceiver<McpEvent>);
+
+impl Events {
+    /// Wait for the first event satisfying `predicate`, skipping the others
+    pub async fn wait_for(&mut self, predicate: impl Fn(&McpEvent) -> bool) -> McpEvent {
+        tokio::time::timeout(EVENT_TIMEOUT, async {
+            loop {
+                let event = self.0.recv().await.expect("event channel closed");
+                if predicate(&event) {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for event")
+    }
+}
+
+/// Create a manager whose events are collected for inspection
+pub fn manager() -> (McpManager, Events) {
+    manager_with(ManagerOptions::default())
+}
+
+/// Create a manager with options whose events are collected for inspection
+pub fn manager_with(options: ManagerOptions) -> (McpManager, Events) {
+    let (sink, events) = collect_events();
+    let manager = McpManager::new(sink, options).expect("failed to create manager");
+    (manager, events)
+}
+
+/// Create a manager that enforces resource limits, with its events collected for inspection
+pub fn shared_manager() -> (Arc<McpManager>, Events) {
+    let (sink, events) = collect_events();
+    let manager = McpManager::new_shared(sink, ManagerOptions::default())
+        .expect("failed to create manager");
+    (manager, events)
+}
+
+/// Sink sending events to a channel, and the receiving end
+fn collect_events() -> (Arc<dyn EventSink>, Events) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let sink = move |event: McpEvent| {
+        let _ = tx.send(event);
+    };
+    (Arc::new(sink), Events(rx))
+}
+
+/// Configuration running the mock server with a script
+pub fn mock_server(id: &str, script: Value) -> ServerConfig {
+    ServerConfig {
+        id: id.to_string(),
+        command: env!("CARGO_BIN_EXE_mock-mcp-server").to_string(),
+        args: vec!["--script".to_string(), script.to_string()],
+        ..Default::default()
+    }
+}
+
+/// Request calling a tool with arguments given as JSON
+pub fn call(server_id: &str, tool_name: &str, inputs: &str) -> ToolExecutionRequest {
+    ToolExecutionRequest {
+        server_id: server_id.to_string(),
+        tool_name: tool_name.to_string(),
+        inputs: inputs.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Text of the first content item of a serialized tool result
+pub fn result_text(result: &str) -> String {
+    let result: Value = serde_json::from_str(result).expect("result is not JSON");
+    result["content"][0]["text"]
+        .as_str()
+        .expect("result has no text content")
+        .to_string()
+}
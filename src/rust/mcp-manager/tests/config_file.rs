@@ -0,0 +1,85 @@
+use mcp_manager::{parse_server_definitions, Error};
+
+fn config_error(content: &str) -> String {
+    match parse_server_definitions(content, "config 'test.json'") {
+        Err(Error::ConfigError(message)) => message,
+        other => panic!("expected a config error, got {:?}", other),
+    }
+}
+
+#[test]
+fn claude_desktop_servers_keep_file_order() {
+    let definitions = parse_server_definitions(
+        r#"{
+            "mcpServers": {
+                "zeta": { "command": "npx", "args": ["-y", "zeta-server"], "env": { "TOKEN": "abc" } },
+                "alpha": { "command": "uvx", "args": ["alpha"], "autoApprove": ["read"] },
+                "off": { "command": "node", "disabled": true },
+                "remote": { "url": "https://example.com/mcp" }
+            },
+            "globalShortcut": "Ctrl+Space"
+        }"#,
+        "config 'test.json'",
+    )
+    .unwrap();
+
+    let ids: Vec<&str> = definitions.servers.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["zeta", "alpha"]);
+    assert_eq!(definitions.servers[0].args, ["-y", "zeta-server"]);
+    assert_eq!(
+        definitions.servers[0].env.as_ref().unwrap()["TOKEN"],
+        "abc"
+    );
+    let skipped: Vec<&str> = definitions.skipped.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(skipped, ["off", "remote"]);
+}
+
+#[test]
+fn vscode_servers_are_accepted() {
+    let definitions = parse_server_definitions(
+        r#"{ "inputs": [], "servers": {
+            "local": { "type": "stdio", "command": "node", "args": ["server.js"] },
+            "web": { "type": "http", "url": "http://localhost:3000/mcp" }
+        } }"#,
+        "config 'mcp.json'",
+    )
+    .unwrap();
+
+    assert_eq!(definitions.servers.len(), 1);
+    assert_eq!(definitions.servers[0].id, "local");
+    assert_eq!(definitions.skipped[0].0, "web");
+}
+
+#[test]
+fn type_errors_name_the_field_and_line() {
+    let message = config_error(
+        r#"{
+  "mcpServers": {
+    "github": { "command": "npx", "args": ["-y", 3] }
+  }
+}"#,
+    );
+    assert!(message.contains("mcpServers.github.args[1]"), "{}", message);
+    assert!(message.contains("line 3"), "{}", message);
+}
+
+#[test]
+fn invalid_definitions_are_rejected() {
+    let message = config_error(r#"{ "mcpServers": { "broken": { "args": [] } } }"#);
+    assert!(message.contains("mcpServers.broken.command"), "{}", message);
+
+    let message = config_error(r#"{ "mcpServers": { "both": { "command": "x", "url": "http://x" } } }"#);
+    assert!(message.contains("mcpServers.both"), "{}", message);
+
+    let message = config_error(r#"{ "servers": { "odd": { "type": "pigeon", "command": "x" } } }"#);
+    assert!(message.contains("servers.odd.type"), "{}", message);
+
+    let message = config_error(r#"{ "mcpServers": { "a": { "command": "x" }, "a": { "command": "y" } } }"#);
+    assert!(message.contains("more than once"), "{}", message);
+
+    let message = config_error(r#"{ "tools": {} }"#);
+    assert!(message.contains("no `mcpServers` or `servers`"), "{}", message);
+
+    let message = config_error(r#"{ "mcpServers": { } "#);
+    assert!(message.contains("line 1"), "{}", message);
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use common::{call, manager, mock_server};
+use mcp_manager::{EventFilter, EventQuery, EventSink, McpEvent, TraceQuery};
+use serde_json::json;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn each_manager_numbers_its_events_in_delivery_order() {
+    let (traced, mut traced_events) = manager();
+    let (plain, _plain_events) = manager();
+
+    let mut config = mock_server("traced", json!({ "tools": [{ "name": "echo" }] }));
+    config.trace = Some(true);
+    traced.start(config).await.unwrap();
+    plain.start(mock_server("plain", json!({}))).await.unwrap();
+    traced.execute_tool(call("traced", "echo", "{}")).await.unwrap();
+    traced.stop("traced").await.unwrap();
+    plain.stop("plain").await.unwrap();
+
+    // Trace events are kept in the trace, every other event in the buffer
+    let mut kept: Vec<i64> = traced
+        .get_recent_events(&EventQuery::default())
+        .iter()
+        .map(McpEvent::sequence)
+        .chain(
+            traced
+                .get_trace("traced", &TraceQuery::default())
+                .iter()
+                .map(|entry| entry.sequence),
+        )
+        .collect();
+    kept.sort();
+    assert_eq!(kept, (1..=kept.len() as i64).collect::<Vec<_>>());
+
+    let delivered = RefCell::new(Vec::new());
+    traced_events
+        .wait_for(|event| {
+            delivered.borrow_mut().push(event.sequence());
+            event.sequence() == kept.len() as i64
+        })
+        .await;
+    assert_eq!(delivered.into_inner(), kept);
+
+    // Another manager counts from the start
+    let plain_events = plain.get_recent_events(&EventQuery::default());
+    assert_eq!(plain_events[0].sequence(), 1);
+}
+
+/// Subscriber collecting the events it is given
+fn collector() -> (Arc<Mutex<Vec<McpEvent>>>, Arc<dyn EventSink>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let received = received.clone();
+        move |event: McpEvent| received.lock().unwrap().push(event)
+    };
+    (received, Arc::new(sink))
+}
+
+/// Type and server of each collected event
+fn described(received: &Mutex<Vec<McpEvent>>) -> Vec<(String, Option<String>)> {
+    received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| (e.event_type().to_string(), e.server_id().map(str::to_string)))
+        .collect()
+}
+
+#[tokio::test]
+async fn subscriptions_only_get_matching_events() {
+    let (manager, mut events) = manager();
+    let (lifecycle, sink) = collector();
+    let filter = EventFilter {
+        event_types: Some(vec!["serverStarted".to_string(), "serverStopped".to_string()]),
+        ..Default::default()
+    };
+    manager.subscribe(filter, sink, None);
+    let (of_server, sink) = collector();
+    let filter = EventFilter {
+        server_ids: Some(vec!["noisy".to_string()]),
+        ..Default::default()
+    };
+    manager.subscribe(filter, sink, None);
+    let (warnings, sink) = collector();
+    let filter = EventFilter {
+        min_level: Some("warn".to_string()),
+        ..Default::default()
+    };
+    manager.subscribe(filter, sink, None);
+
+    manager.start(mock_server("quiet", json!({}))).await.unwrap();
+    let script = json!({ "stderr": ["INFO all good", "WARN running low"] });
+    manager.start(mock_server("noisy", script)).await.unwrap();
+    events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains("running low")))
+        .await;
+    manager.stop("quiet").await.unwrap();
+    manager.stop("noisy").await.unwrap();
+
+    let server = |id: &str| Some(id.to_string());
+    assert_eq!(
+        described(&lifecycle),
+        [
+            ("serverStarted".to_string(), server("quiet")),
+            ("serverStarted".to_string(), server("noisy")),
+            ("serverStopped".to_string(), server("quiet")),
+            ("serverStopped".to_string(), server("noisy")),
+        ]
+    );
+
+    let of_server = described(&of_server);
+    assert!(of_server.contains(&("serverStarted".to_string(), server("noisy"))));
+    assert!(
+        of_server.iter().all(|(_, server_id)| *server_id == server("noisy")),
+        "{:?}",
+        of_server
+    );
+
+    // Only log events are held to the minimum level
+    let warnings = warnings.lock().unwrap();
+    let messages: Vec<&str> = warnings
+        .iter()
+        .filter_map(|e| match e {
+            McpEvent::Log(log) => Some(log.message.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(messages.iter().any(|m| m.contains("running low")), "{:?}", messages);
+    assert!(!messages.iter().any(|m| m.contains("all good")), "{:?}", messages);
+    assert!(warnings.iter().any(|e| matches!(e, McpEvent::ServerStarted(_))));
+}
+
+#[tokio::test]
+async fn subscriptions_replay_buffered_events_and_can_be_removed() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("first", json!({}))).await.unwrap();
+    manager.stop("first").await.unwrap();
+    let buffered = manager.get_recent_events(&EventQuery::default());
+    let started = buffered
+        .iter()
+        .find(|e| matches!(e, McpEvent::ServerStarted(_)))
+        .unwrap()
+        .sequence();
+
+    // Events newer than the given sequence number are replayed, as the filter allows
+    let (replayed, sink) = collector();
+    manager.subscribe(EventFilter::default(), sink, Some(started));
+    let expected: Vec<i64> = buffered
+        .iter()
+        .map(McpEvent::sequence)
+        .filter(|&sequence| sequence > started)
+        .collect();
+    let sequences: Vec<i64> = replayed.lock().unwrap().iter().map(McpEvent::sequence).collect();
+    assert_eq!(sequences, expected);
+
+    let (stops, sink) = collector();
+    let filter = EventFilter {
+        event_types: Some(vec!["serverStopped".to_string()]),
+        ..Default::default()
+    };
+    let id = manager.subscribe(filter, sink, Some(0));
+    assert_eq!(described(&stops), [("serverStopped".to_string(), Some("first".to_string()))]);
+
+    let (live, sink) = collector();
+    manager.subscribe(EventFilter::default(), sink, None);
+    assert!(live.lock().unwrap().is_empty());
+
+    // A removed subscription gets nothing more
+    assert!(manager.unsubscribe(id));
+    assert!(!manager.unsubscribe(id));
+    manager.start(mock_server("second", json!({}))).await.unwrap();
+    manager.stop("second").await.unwrap();
+    assert_eq!(stops.lock().unwrap().len(), 1);
+    assert!(!live.lock().unwrap().is_empty());
+}
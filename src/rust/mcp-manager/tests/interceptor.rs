@@ -0,0 +1,110 @@
+mod common;
+
+use common::{call, manager, mock_server, result_text};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use mcp_manager::{ArgumentDefaults, Error, ResultTruncation, ToolCall, ToolInterceptor};
+use serde_json::{json, Map, Value};
+use std::sync::{Arc, Mutex};
+
+/// Records when its hooks run and tags the arguments it passes on
+struct Tagger {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl ToolInterceptor for Tagger {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn before_call(&self, mut call: ToolCall) -> BoxFuture<'static, mcp_manager::Result<ToolCall>> {
+        self.log.lock().unwrap().push(format!("before {}", self.name));
+        call.arguments.insert(self.name.to_string(), json!(true));
+        futures::future::ready(Ok(call)).boxed()
+    }
+
+    fn after_call(&self, _call: &ToolCall, result: Value) -> BoxFuture<'static, mcp_manager::Result<Value>> {
+        self.log.lock().unwrap().push(format!("after {}", self.name));
+        futures::future::ready(Ok(result)).boxed()
+    }
+}
+
+/// Rejects every call
+struct Blocker;
+
+impl ToolInterceptor for Blocker {
+    fn name(&self) -> &str {
+        "blocker"
+    }
+
+    fn before_call(&self, _call: ToolCall) -> BoxFuture<'static, mcp_manager::Result<ToolCall>> {
+        futures::future::ready(Err(Error::Other("not today".to_string()))).boxed()
+    }
+}
+
+#[tokio::test]
+async fn interceptors_wrap_calls_in_order() {
+    let (manager, _events) = manager();
+    let script = json!({ "tools": [{ "name": "echo" }] });
+    manager.start(mock_server("tools", script)).await.unwrap();
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    for name in ["first", "second"] {
+        manager.add_interceptor(Arc::new(Tagger {
+            name,
+            log: log.clone(),
+        }));
+    }
+    let result = manager.execute_tool(call("tools", "echo", "{}")).await.unwrap();
+    let arguments: Value = serde_json::from_str(&result_text(&result)).unwrap();
+    assert_eq!(arguments, json!({ "first": true, "second": true }));
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["before first", "before second", "after second", "after first"]
+    );
+
+    let blocker = manager.add_interceptor(Arc::new(Blocker));
+    let result = manager.execute_tool(call("tools", "echo", "{}")).await;
+    assert!(
+        matches!(&result, Err(Error::ToolExecutionError(message)) if message.contains("'blocker'") && message.contains("not today")),
+        "{:?}",
+        result
+    );
+    assert!(manager.remove_interceptor(blocker));
+    assert!(!manager.remove_interceptor(blocker));
+    manager.execute_tool(call("tools", "echo", "{}")).await.unwrap();
+
+    manager.stop("tools").await.unwrap();
+}
+
+#[tokio::test]
+async fn builtin_interceptors_fill_defaults_and_truncate() {
+    let (manager, _events) = manager();
+    let script = json!({ "tools": [{ "name": "echo" }, { "name": "other" }] });
+    manager.start(mock_server("tools", script)).await.unwrap();
+
+    let mut defaults = Map::new();
+    defaults.insert("limit".to_string(), json!(10));
+    defaults.insert("query".to_string(), json!("default"));
+    manager.add_interceptor(Arc::new(ArgumentDefaults {
+        server_id: None,
+        tool_name: Some("echo".to_string()),
+        defaults,
+    }));
+    manager.add_interceptor(Arc::new(ResultTruncation { max_length: 30 }));
+
+    // Given arguments win over defaults
+    let result = manager.execute_tool(call("tools", "echo", r#"{"query":"rust"}"#)).await.unwrap();
+    assert_eq!(result_text(&result), r#"{"limit":10,"query":"rust"}"#);
+    let result = manager.execute_tool(call("tools", "other", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "{}");
+
+    let long = format!(r#"{{"query":"{}"}}"#, "x".repeat(100));
+    let result = manager.execute_tool(call("tools", "echo", &long)).await.unwrap();
+    let text = result_text(&result);
+    assert!(text.starts_with(r#"{"limit":10,"query":"xxxxxxxx"#), "{}", text);
+    assert!(text.ends_with("\n[truncated 93 characters]"), "{}", text);
+
+    manager.stop("tools").await.unwrap();
+}
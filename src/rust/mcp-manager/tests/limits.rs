@@ -0,0 +1,229 @@
+mod common;
+
+use common::{call, mock_server, shared_manager, Events};
+use mcp_manager::{
+    Error, LimitAction, LimitLevel, LimitedResource, McpEvent, McpManager, ResourceLimitEvent,
+    ResourceLimits, ServerConfig, ServerInfo,
+};
+use serde_json::json;
+use std::cell::Cell;
+use std::time::Duration;
+
+/// Server with a `grow` tool allocating `grow_mb` and a `busy` tool spinning for `busy_ms`
+fn limited_server(id: &str, grow_mb: u32, busy_ms: u32, limits: ResourceLimits) -> ServerConfig {
+    let script = json!({ "tools": [
+        { "name": "grow", "allocateMb": grow_mb },
+        { "name": "busy", "busyMs": busy_ms },
+    ] });
+    let mut config = mock_server(id, script);
+    config.limits = Some(ResourceLimits {
+        check_interval_ms: Some(50),
+        ..limits
+    });
+    config
+}
+
+async fn next_limit_event(events: &mut Events, server_id: &str) -> ResourceLimitEvent {
+    let event = events
+        .wait_for(|e| matches!(e, McpEvent::ResourceLimit(limit) if limit.server_id == server_id))
+        .await;
+    let McpEvent::ResourceLimit(event) = event else {
+        unreachable!()
+    };
+    event
+}
+
+#[tokio::test]
+async fn hard_memory_limit_restarts_the_server() {
+    let (manager, mut events) = shared_manager();
+    let limits = ResourceLimits {
+        soft_memory_mb: Some(60),
+        hard_memory_mb: Some(120),
+        ..Default::default()
+    };
+    manager.start(limited_server("growing", 80, 0, limits)).await.unwrap();
+    let pid = manager.list_servers()[0].pid.unwrap();
+
+    manager.execute_tool(call("growing", "grow", "{}")).await.unwrap();
+    let warning = next_limit_event(&mut events, "growing").await;
+    assert_eq!(warning.breach.resource, LimitedResource::Memory);
+    assert_eq!(warning.breach.level, LimitLevel::Soft);
+    assert!(warning.breach.value >= 60.0);
+    assert_eq!(warning.breach.limit, 60.0);
+    assert_eq!(warning.action, None);
+    assert_eq!(manager.list_servers()[0].limit_restarts, 0);
+
+    // The server may be restarted before it answers
+    let _ = manager.execute_tool(call("growing", "grow", "{}")).await;
+    let breach = next_limit_event(&mut events, "growing").await;
+    assert_eq!(breach.breach.level, LimitLevel::Hard);
+    assert_eq!(breach.breach.limit, 120.0);
+    assert_eq!(breach.action, Some(LimitAction::Restart));
+
+    // The server is asked to exit by closing its input; its last log line may trail the restart
+    let (input_closed, restarted) = (Cell::new(false), Cell::new(false));
+    events
+        .wait_for(|e| {
+            match e {
+                McpEvent::Log(log) if log.message.contains("input closed") => input_closed.set(true),
+                McpEvent::ServerStarted(started) if started.server_id == "growing" => restarted.set(true),
+                _ => {}
+            }
+            input_closed.get() && restarted.get()
+        })
+        .await;
+    let info = wait_for_restart(&manager).await;
+    assert_ne!(info.pid, Some(pid));
+    let last = info.last_limit_breach.unwrap();
+    assert_eq!(last.level, LimitLevel::Hard);
+    assert_eq!(last.resource, LimitedResource::Memory);
+
+    manager.stop("growing").await.unwrap();
+}
+
+/// Wait until the server shows up as restarted once for a limit
+async fn wait_for_restart(manager: &McpManager) -> ServerInfo {
+    for _ in 0..100 {
+        if let Some(info) = manager.list_servers().into_iter().find(|s| s.limit_restarts == 1) {
+            return info;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("server was not restarted: {:?}", manager.list_servers());
+}
+
+#[tokio::test]
+async fn hard_limit_can_stop_the_server() {
+    let (manager, mut events) = shared_manager();
+    let limits = ResourceLimits {
+        hard_memory_mb: Some(40),
+        action: Some(LimitAction::Stop),
+        grace_period_ms: Some(300),
+        ..Default::default()
+    };
+    let mut config = limited_server("stopped", 60, 0, limits);
+    config.args = vec![
+        "--script".to_string(),
+        json!({ "tools": [{ "name": "grow", "allocateMb": 60 }], "ignoreInputClose": true }).to_string(),
+    ];
+    manager.start(config).await.unwrap();
+
+    let _ = manager.execute_tool(call("stopped", "grow", "{}")).await;
+    let breach = next_limit_event(&mut events, "stopped").await;
+    assert_eq!(breach.action, Some(LimitAction::Stop));
+    let breached = std::time::Instant::now();
+
+    // A server staying up once its input is closed gets SIGTERM after the grace period
+    events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains("input closed")))
+        .await;
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerStopped(stopped) if stopped.server_id == "stopped"))
+        .await;
+    assert!(breached.elapsed() >= Duration::from_millis(300));
+    assert!(manager.list_servers().is_empty());
+    let status = manager.get_limit_status("stopped").unwrap();
+    assert!(status.stopped);
+    assert_eq!(status.restarts, 0);
+    assert_eq!(status.last_breach.unwrap().limit, breach.breach.limit);
+    assert!(manager
+        .get_recent_events(&Default::default())
+        .iter()
+        .all(|e| !matches!(e, McpEvent::Log(log) if log.level == "error")));
+}
+
+#[tokio::test]
+async fn restarts_are_capped_within_the_window() {
+    let (manager, mut events) = shared_manager();
+    let limits = ResourceLimits {
+        hard_memory_mb: Some(40),
+        max_restarts: Some(1),
+        ..Default::default()
+    };
+    manager.start(limited_server("flapping", 60, 0, limits)).await.unwrap();
+
+    let _ = manager.execute_tool(call("flapping", "grow", "{}")).await;
+    let breach = next_limit_event(&mut events, "flapping").await;
+    assert_eq!(breach.action, Some(LimitAction::Restart));
+    wait_for_restart(&manager).await;
+
+    // The second breach finds the only restart used up
+    let _ = manager.execute_tool(call("flapping", "grow", "{}")).await;
+    let breach = next_limit_event(&mut events, "flapping").await;
+    assert_eq!(breach.action, Some(LimitAction::Stop));
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerStopped(stopped) if stopped.server_id == "flapping"))
+        .await;
+    assert!(manager.list_servers().is_empty());
+    let status = manager.get_limit_status("flapping").unwrap();
+    assert!(status.stopped);
+    assert_eq!(status.restarts, 1);
+    assert!(manager.get_recent_events(&Default::default()).iter().any(|e| {
+        matches!(e, McpEvent::Log(log) if log.level == "error" && log.message.contains("limit of 1 restarts"))
+    }));
+}
+
+#[tokio::test]
+async fn cpu_limits_need_sustained_usage() {
+    let (manager, mut events) = shared_manager();
+    let limits = ResourceLimits {
+        soft_cpu_percent: Some(20),
+        cpu_window_secs: Some(1),
+        ..Default::default()
+    };
+    manager.start(limited_server("spinning", 0, 600, limits.clone())).await.unwrap();
+    manager.start(limited_server("sustained", 0, 3000, limits)).await.unwrap();
+
+    // A short burst stays below the window
+    manager.execute_tool(call("spinning", "busy", "{}")).await.unwrap();
+    manager.execute_tool(call("sustained", "busy", "{}")).await.unwrap();
+    let warning = next_limit_event(&mut events, "sustained").await;
+    assert_eq!(warning.breach.resource, LimitedResource::Cpu);
+    assert_eq!(warning.breach.level, LimitLevel::Soft);
+    assert!(manager
+        .get_recent_events(&Default::default())
+        .iter()
+        .all(|e| !matches!(e, McpEvent::ResourceLimit(limit) if limit.server_id == "spinning")));
+
+    manager.stop("spinning").await.unwrap();
+    manager.stop("sustained").await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_limits_fail_the_start() {
+    let (manager, _events) = shared_manager();
+    let limits = ResourceLimits {
+        soft_memory_mb: Some(200),
+        hard_memory_mb: Some(100),
+        ..Default::default()
+    };
+    let result = manager.start(limited_server("inverted", 0, 0, limits)).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+
+    let mut config = mock_server("unchecked", json!({}));
+    config.limits = Some(ResourceLimits {
+        check_interval_ms: Some(0),
+        ..Default::default()
+    });
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+
+    let mut config = mock_server("windowless", json!({}));
+    config.limits = Some(ResourceLimits {
+        restart_window_secs: Some(0),
+        ..Default::default()
+    });
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+    assert!(manager.list_servers().is_empty());
+
+    // A manager that cannot restart servers does not take limits at all
+    let (unshared, _events) = common::manager();
+    let limits = ResourceLimits {
+        hard_memory_mb: Some(100),
+        ..Default::default()
+    };
+    let result = unshared.start(limited_server("unshared", 0, 0, limits)).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))), "{:?}", result);
+    assert!(unshared.list_servers().is_empty());
+}
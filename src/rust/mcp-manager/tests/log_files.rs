@@ -0,0 +1,50 @@
+mod common;
+
+use common::{manager_with, mock_server};
+use mcp_manager::{ManagerOptions, McpEvent};
+use serde_json::json;
+
+#[tokio::test]
+async fn log_files_are_rotated_and_kept_apart() {
+    let dir = std::env::temp_dir().join(format!("mcp-manager-logs-{}", std::process::id()));
+    let (manager, mut events) = manager_with(ManagerOptions {
+        log_dir: Some(dir.to_string_lossy().into_owned()),
+        max_log_file_size: Some(4096),
+        max_log_files: Some(2),
+        ..Default::default()
+    });
+
+    // Both IDs become `a_b` as file names
+    for (id, word) in [("a/b", "slash"), ("a b", "space")] {
+        let mut stderr: Vec<String> = (0..300).map(|i| format!("INFO {} line {}", word, i)).collect();
+        stderr.push(format!("INFO {} done", word));
+        manager.start(mock_server(id, json!({ "stderr": stderr }))).await.unwrap();
+        let done = format!("{} done", word);
+        events
+            .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains(&done)))
+            .await;
+    }
+
+    for (id, word, other) in [("a/b", "slash", "space"), ("a b", "space", "slash")] {
+        let tail = manager.get_log_tail(id, 3).unwrap();
+        assert_eq!(tail.len(), 3, "{:?}", tail);
+        assert!(tail[2].ends_with(&format!("{} done", word)), "{:?}", tail);
+
+        let all = manager.get_log_tail(id, usize::MAX).unwrap();
+        assert!(all.iter().all(|line| !line.contains(other)), "{:?}", all);
+        // Rotation dropped the oldest lines
+        assert!(all.iter().all(|line| !line.ends_with(&format!("{} line 0", word))));
+    }
+
+    let mut files: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files.len(), 6, "{:?}", files);
+    assert!(files.iter().all(|name| name.starts_with("a_b~")), "{:?}", files);
+
+    manager.stop("a/b").await.unwrap();
+    manager.stop("a b").await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
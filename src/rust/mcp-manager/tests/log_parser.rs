@@ -0,0 +1,116 @@
+mod common;
+
+use common::{manager, mock_server};
+use mcp_manager::McpEvent;
+use serde_json::json;
+
+/// Last line written by the servers below, after the lines under test
+const DONE: &str = "INFO stderr done";
+
+/// Level and message of the log events a server's stderr lines become
+async fn stderr_logs(lines: &[&str], log_level_pattern: Option<&str>) -> Vec<(String, String)> {
+    let (manager, mut events) = manager();
+    let stderr: Vec<&str> = lines.iter().copied().chain([DONE]).collect();
+    let mut config = mock_server("logger", json!({ "stderr": stderr }));
+    config.log_level_pattern = log_level_pattern.map(str::to_string);
+    manager.start(config).await.unwrap();
+    events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message == DONE))
+        .await;
+    manager.stop("logger").await.unwrap();
+
+    manager
+        .get_recent_events(&Default::default())
+        .into_iter()
+        .filter_map(|e| match e {
+            McpEvent::Log(log) if log.server_id.as_deref() == Some("logger") => {
+                Some((log.level, log.message))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether an entry with the given level and message was logged
+fn logged(logs: &[(String, String)], level: &str, message: &str) -> bool {
+    logs.iter().any(|(l, m)| l == level && m == message)
+}
+
+#[tokio::test]
+async fn python_tracebacks_are_one_error() {
+    let traceback = [
+        "Traceback (most recent call last):",
+        "  File \"server.py\", line 12, in handle",
+        "    return cache[key]",
+        "KeyError: 'missing'",
+    ];
+    let logs = stderr_logs(&traceback, None).await;
+    assert!(logged(&logs, "error", &traceback.join("\n")), "{:?}", logs);
+    assert!(!logs.iter().any(|(_, m)| m == "KeyError: 'missing'"), "{:?}", logs);
+}
+
+#[tokio::test]
+async fn chained_python_tracebacks_stay_together() {
+    for chain_message in [
+        "During handling of the above exception, another exception occurred:",
+        "The above exception was the direct cause of the following exception:",
+    ] {
+        let traceback = [
+            "Traceback (most recent call last):",
+            "  File \"server.py\", line 12, in handle",
+            "    return cache[key]",
+            "KeyError: 'missing'",
+            "",
+            chain_message,
+            "",
+            "Traceback (most recent call last):",
+            "  File \"server.py\", line 14, in handle",
+            "    raise LookupFailed(key)",
+            "LookupFailed: missing",
+        ];
+        let lines: Vec<&str> = traceback.iter().copied().chain([""]).collect();
+        let logs = stderr_logs(&lines, None).await;
+        assert!(logged(&logs, "error", &traceback.join("\n")), "{:?}", logs);
+        assert!(!logs.iter().any(|(_, m)| m == chain_message), "{:?}", logs);
+    }
+}
+
+#[tokio::test]
+async fn node_stack_traces_are_one_error() {
+    let stack = [
+        "TypeError: Cannot read properties of undefined (reading 'id')",
+        "    at handle (/srv/index.js:10:5)",
+        "    at process.processTicksAndRejections (node:internal/process/task_queues:95:5)",
+    ];
+    let logs = stderr_logs(&stack, None).await;
+    assert!(logged(&logs, "error", &stack.join("\n")), "{:?}", logs);
+    assert!(!logs.iter().any(|(_, m)| m.starts_with("    at ")), "{:?}", logs);
+}
+
+#[tokio::test]
+async fn json_lines_give_their_level_and_message() {
+    let lines = [
+        r#"{"level":"warn","msg":"disk almost full"}"#,
+        r#"{"level":50,"msg":"pino failure","pid":42}"#,
+        r#"{"severity":"DEBUG","message":"cache details"}"#,
+        r#"{"levelname":"CRITICAL","message":"logging failure"}"#,
+    ];
+    let logs = stderr_logs(&lines, None).await;
+    assert!(logged(&logs, "warn", "disk almost full"), "{:?}", logs);
+    assert!(logged(&logs, "error", "pino failure"), "{:?}", logs);
+    assert!(logged(&logs, "debug", "cache details"), "{:?}", logs);
+    assert!(logged(&logs, "error", "logging failure"), "{:?}", logs);
+}
+
+#[tokio::test]
+async fn custom_level_pattern_comes_first() {
+    let lines = ["request failed level=error", "INFO retrying level=warn", "WARN no level here"];
+    let logs = stderr_logs(&lines, Some(r"level=(?P<level>\w+)")).await;
+    assert!(logged(&logs, "error", "request failed level=error"), "{:?}", logs);
+    assert!(logged(&logs, "warn", "INFO retrying level=warn"), "{:?}", logs);
+    // Lines the pattern does not match fall back to the usual detection
+    assert!(logged(&logs, "warn", "WARN no level here"), "{:?}", logs);
+
+    let logs = stderr_logs(&lines[..1], None).await;
+    assert!(logged(&logs, "info", "request failed level=error"), "{:?}", logs);
+}
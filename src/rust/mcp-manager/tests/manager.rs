@@ -0,0 +1,483 @@
+mod common;
+
+use common::{call, manager, mock_server, result_text};
+use mcp_manager::{
+    ApplyAction, ApplyOptions, ConfigWatchOptions, Error, EventQuery, McpEvent, ServerConfig,
+};
+use std::sync::Arc;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn start_lists_tools() {
+    let (manager, mut events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({ "tools": [{ "name": "first", "description": "The first" }, { "name": "second" }] }),
+        ))
+        .await
+        .unwrap();
+
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerStarted(e) if e.server_id == "mock"))
+        .await;
+    let mut names: Vec<String> = manager.get_tools().into_iter().map(|t| t.tool_name).collect();
+    names.sort();
+    assert_eq!(names, ["first", "second"]);
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn changed_tool_list_is_fetched_again() {
+    let (manager, mut events) = manager();
+    let mut config = mock_server(
+        "mock",
+        json!({
+            "tools": [{ "name": "early" }],
+            "listChangedAfterMs": 200,
+            "toolsAfterChange": [{ "name": "late", "text": "late" }, { "name": "hidden" }],
+        }),
+    );
+    config.exclude_tools = Some(vec!["hidden".to_string()]);
+    config.aliases = Some(HashMap::from([("late".to_string(), "later".to_string())]));
+    manager.start(config).await.unwrap();
+
+    let event = events
+        .wait_for(|e| matches!(e, McpEvent::ToolsUpdated(e) if e.tools.iter().any(|t| t.tool_name == "later")))
+        .await;
+    let McpEvent::ToolsUpdated(event) = event else {
+        unreachable!()
+    };
+    assert_eq!(event.tools.len(), 1);
+    let names: Vec<String> = manager.get_tools().into_iter().map(|t| t.tool_name).collect();
+    assert_eq!(names, ["later"]);
+
+    let result = manager.execute_tool(call("mock", "later", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "late");
+    let result = manager.execute_tool(call("mock", "early", "{}")).await;
+    assert!(matches!(result, Err(Error::ToolNotFound(_))), "{:?}", result);
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn duplicate_start_is_rejected() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("mock", json!({}))).await.unwrap();
+
+    let result = manager.start(mock_server("mock", json!({}))).await;
+    assert!(matches!(result, Err(Error::Other(_))));
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn stop_removes_server() {
+    let (manager, mut events) = manager();
+    manager.start(mock_server("mock", json!({}))).await.unwrap();
+
+    manager.stop("mock").await.unwrap();
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerStopped(e) if e.server_id == "mock"))
+        .await;
+    assert!(manager.get_tools().is_empty());
+    assert!(matches!(
+        manager.stop("mock").await,
+        Err(Error::ServerNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn execute_echoes_arguments() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("mock", json!({}))).await.unwrap();
+
+    let result = manager
+        .execute_tool(call("mock", "echo", r#"{"greeting":"hello"}"#))
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), r#"{"greeting":"hello"}"#);
+
+    for inputs in ["[1, 2]", "\"hello\"", "null"] {
+        let result = manager.execute_tool(call("mock", "echo", inputs)).await;
+        assert!(
+            matches!(&result, Err(Error::ToolExecutionError(message)) if message.contains("must be a JSON object")),
+            "{}: {:?}",
+            inputs,
+            result
+        );
+    }
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn execute_returns_scripted_results() {
+    let (manager, _events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({ "tools": [
+                { "name": "text", "text": "scripted" },
+                { "name": "failing", "text": "bad input", "isError": true },
+                { "name": "broken", "error": "internal failure" },
+            ] }),
+        ))
+        .await
+        .unwrap();
+
+    let result = manager.execute_tool(call("mock", "text", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "scripted");
+
+    let result = manager.execute_tool(call("mock", "failing", "{}")).await.unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["isError"], json!(true));
+
+    let result = manager.execute_tool(call("mock", "broken", "{}")).await;
+    assert!(
+        matches!(&result, Err(Error::ToolExecutionError(message)) if message.contains("internal failure")),
+        "unexpected result: {:?}",
+        result
+    );
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn execute_unknown_tool_fails() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("mock", json!({}))).await.unwrap();
+
+    let result = manager.execute_tool(call("mock", "missing", "{}")).await;
+    assert!(matches!(result, Err(Error::ToolNotFound(_))));
+    let result = manager.execute_tool(call("other", "echo", "{}")).await;
+    assert!(matches!(result, Err(Error::ServerNotFound(_))));
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn slow_tool_times_out() {
+    let (manager, _events) = manager();
+    let mut config = mock_server(
+        "mock",
+        json!({ "tools": [{ "name": "slow", "delayMs": 2000 }] }),
+    );
+    config.tool_timeout_ms = Some(100);
+    manager.start(config).await.unwrap();
+
+    let result = manager.execute_tool(call("mock", "slow", "{}")).await;
+    assert!(
+        matches!(&result, Err(Error::ToolExecutionError(message)) if message.contains("timed out after 100 ms")),
+        "unexpected result: {:?}",
+        result
+    );
+
+    // The request's own timeout takes precedence over the server's
+    let mut request = call("mock", "slow", "{}");
+    request.timeout_ms = Some(5000);
+    assert!(manager.execute_tool(request).await.is_ok());
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn progress_is_reported() {
+    let (manager, mut events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({ "tools": [{ "name": "work", "progress": 3 }] }),
+        ))
+        .await
+        .unwrap();
+
+    let mut request = call("mock", "work", "{}");
+    request.progress_token = Some("job-1".to_string());
+    manager.execute_tool(request).await.unwrap();
+
+    let event = events
+        .wait_for(|e| matches!(e, McpEvent::Progress(p) if p.progress == 3))
+        .await;
+    let McpEvent::Progress(progress) = event else {
+        unreachable!()
+    };
+    assert_eq!(progress.server_id, "mock");
+    assert_eq!(progress.progress_token, "job-1");
+    assert_eq!(progress.total, Some(3));
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn stderr_becomes_log_events() {
+    let (manager, mut events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({ "stderr": ["ERROR disk is full"], "stderrSpam": 50 }),
+        ))
+        .await
+        .unwrap();
+
+    let event = events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains("disk is full")))
+        .await;
+    let McpEvent::Log(log) = event else {
+        unreachable!()
+    };
+    assert_eq!(log.level, "error");
+    assert_eq!(log.server_id.as_deref(), Some("mock"));
+    events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains("spam line 49")))
+        .await;
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn crash_during_call_is_detected() {
+    let (manager, mut events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({ "tools": [{ "name": "boom", "crash": true }, { "name": "echo" }] }),
+        ))
+        .await
+        .unwrap();
+
+    assert!(manager.execute_tool(call("mock", "boom", "{}")).await.is_err());
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerCrashed(e) if e.server_id == "mock"))
+        .await;
+    assert!(manager.get_tools().is_empty());
+
+    // The crashed server's ID can be used again
+    manager
+        .start(mock_server("mock", json!({})))
+        .await
+        .unwrap();
+    assert!(manager.execute_tool(call("mock", "echo", "{}")).await.is_ok());
+
+    manager.stop("mock").await.unwrap();
+}
+
+#[tokio::test]
+async fn scheduled_crash_is_detected() {
+    let (manager, mut events) = manager();
+    manager
+        .start(mock_server("mock", json!({ "crashAfterMs": 200 })))
+        .await
+        .unwrap();
+
+    events
+        .wait_for(|e| matches!(e, McpEvent::ServerCrashed(e) if e.server_id == "mock"))
+        .await;
+    assert!(matches!(
+        manager.execute_tool(call("mock", "echo", "{}")).await,
+        Err(Error::ServerNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn resources_and_prompts_are_listed() {
+    let (manager, _events) = manager();
+    manager
+        .start(mock_server(
+            "mock",
+            json!({
+                "resources": [{ "uri": "file:///notes.txt", "name": "notes", "mimeType": "text/plain" }],
+                "prompts": [{
+                    "name": "summarize",
+                    "arguments": [{ "name": "text", "required": true }, { "name": "style" }],
+                }],
+            }),
+        ))
+        .await
+        .unwrap();
+    manager.start(mock_server("plain", json!({}))).await.unwrap();
+
+    let resources = manager.list_resources("mock").await.unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].uri, "file:///notes.txt");
+    assert_eq!(resources[0].mime_type.as_deref(), Some("text/plain"));
+
+    let prompts = manager.list_prompts("mock").await.unwrap();
+    assert_eq!(prompts.len(), 1);
+    let required: Vec<bool> = prompts[0].arguments.iter().map(|a| a.required).collect();
+    assert_eq!(required, [true, false]);
+
+    // Servers without the capability have nothing to list
+    assert!(manager.list_resources("plain").await.unwrap().is_empty());
+    assert!(manager.list_prompts("plain").await.unwrap().is_empty());
+
+    manager.stop("mock").await.unwrap();
+    manager.stop("plain").await.unwrap();
+}
+
+#[tokio::test]
+async fn start_from_config_reconciles_running_servers() {
+    let (manager, _events) = manager();
+    let command = env!("CARGO_BIN_EXE_mock-mcp-server");
+    manager.start(mock_server("stale", json!({}))).await.unwrap();
+
+    let config = json!({ "mcpServers": {
+        "first": { "command": command },
+        "second": { "command": command, "args": ["--script", r#"{"tools":[{"name":"other"}]}"#] },
+    } });
+    manager.start_from_config(&config.to_string()).await.unwrap();
+
+    let mut servers: Vec<String> = manager.get_tools().into_iter().map(|t| t.server_id).collect();
+    servers.sort();
+    assert_eq!(servers, ["first", "second"]);
+
+    // Dropping a server stops it, and unchanged servers keep running
+    let config = json!({ "mcpServers": { "first": { "command": command } } });
+    manager.start_from_config(&config.to_string()).await.unwrap();
+    let servers: Vec<String> = manager.get_tools().into_iter().map(|t| t.server_id).collect();
+    assert_eq!(servers, ["first"]);
+    let starts = manager.get_recent_events(&EventQuery {
+        server_id: Some("first".to_string()),
+        event_types: Some(vec!["serverStarted".to_string()]),
+        ..Default::default()
+    });
+    assert_eq!(starts.len(), 1);
+
+    manager.stop("first").await.unwrap();
+}
+
+#[tokio::test]
+async fn apply_reports_each_server() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("kept", json!({}))).await.unwrap();
+    manager.start(mock_server("changed", json!({}))).await.unwrap();
+    manager.start(mock_server("dropped", json!({}))).await.unwrap();
+
+    let broken = ServerConfig {
+        id: "broken".to_string(),
+        command: "/nonexistent/mcp-server".to_string(),
+        ..Default::default()
+    };
+    let desired = vec![
+        mock_server("kept", json!({})),
+        mock_server("changed", json!({ "tools": [{ "name": "other" }] })),
+        mock_server("added", json!({})),
+        broken,
+    ];
+    let results = manager
+        .apply(desired, ApplyOptions { concurrency: Some(2) })
+        .await
+        .unwrap();
+
+    let actions: Vec<(&str, ApplyAction)> =
+        results.iter().map(|r| (r.server_id.as_str(), r.action)).collect();
+    assert_eq!(
+        actions,
+        [
+            ("kept", ApplyAction::Unchanged),
+            ("changed", ApplyAction::Restarted),
+            ("added", ApplyAction::Started),
+            ("broken", ApplyAction::Failed),
+            ("dropped", ApplyAction::Stopped),
+        ]
+    );
+    // The hash is FNV-1a of the configuration's JSON, so it is the same in every build
+    let kept = serde_json::to_value(mock_server("kept", json!({}))).unwrap().to_string();
+    let fnv1a = kept.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    assert_eq!(results[0].config_hash, Some(format!("{:016x}", fnv1a)));
+    assert!(results[3].error.is_some());
+    assert!(results[4].config_hash.is_none());
+
+    let mut tools: Vec<String> = manager.get_tools().into_iter().map(|t| t.tool_name).collect();
+    tools.sort();
+    assert_eq!(tools, ["echo", "echo", "other"]);
+
+    for id in ["kept", "changed", "added"] {
+        manager.stop(id).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn invalid_log_level_fails_before_spawning() {
+    let (manager, _events) = manager();
+    let mut config = mock_server("noisy", json!({}));
+    config.log_level = Some("loud".to_string());
+
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))), "{:?}", result);
+    assert!(manager.list_servers().is_empty());
+    assert!(manager.get_recent_events(&EventQuery::default()).is_empty());
+}
+
+#[tokio::test]
+async fn invalid_log_level_is_a_config_error_at_runtime() {
+    let (manager, _events) = manager();
+    manager.start(mock_server("noisy", json!({}))).await.unwrap();
+
+    let result = manager.set_log_level("noisy", "loud").await;
+    assert!(matches!(result, Err(Error::ConfigError(_))), "{:?}", result);
+
+    manager.stop("noisy").await.unwrap();
+}
+
+#[tokio::test]
+async fn apply_rejects_duplicate_ids() {
+    let (manager, _events) = manager();
+    let desired = vec![mock_server("twice", json!({})), mock_server("twice", json!({}))];
+    let result = manager.apply(desired, ApplyOptions::default()).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+    assert!(manager.get_tools().is_empty());
+}
+
+#[tokio::test]
+async fn watched_config_is_reloaded() {
+    let (manager, mut events) = manager();
+    let manager = Arc::new(manager);
+    let command = env!("CARGO_BIN_EXE_mock-mcp-server");
+    let dir = std::env::temp_dir().join(format!("mcp-manager-watch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mcp.json");
+    std::fs::write(&path, json!({ "mcpServers": { "first": { "command": command } } }).to_string())
+        .unwrap();
+
+    let results = manager
+        .watch_config(path.to_str().unwrap(), ConfigWatchOptions { debounce_ms: Some(50) })
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].action, ApplyAction::Started);
+
+    let config = json!({ "mcpServers": {
+        "first": { "command": command },
+        "second": { "command": command },
+    } });
+    std::fs::write(&path, config.to_string()).unwrap();
+    let event = events.wait_for(|e| matches!(e, McpEvent::ConfigReloaded(_))).await;
+    let McpEvent::ConfigReloaded(reloaded) = event else {
+        unreachable!()
+    };
+    assert_eq!(reloaded.error, None);
+    let changes: Vec<(&str, ApplyAction)> =
+        reloaded.changes.iter().map(|r| (r.server_id.as_str(), r.action)).collect();
+    assert_eq!(changes, [("second", ApplyAction::Started)]);
+
+    // An invalid file is reported and leaves the servers alone
+    std::fs::write(&path, "{ \"mcpServers\": ").unwrap();
+    let event = events.wait_for(|e| matches!(e, McpEvent::ConfigReloaded(_))).await;
+    let McpEvent::ConfigReloaded(reloaded) = event else {
+        unreachable!()
+    };
+    assert!(reloaded.error.is_some());
+    assert!(reloaded.changes.is_empty());
+    assert_eq!(manager.get_tools().len(), 2);
+
+    manager.unwatch_config();
+    for id in ["first", "second"] {
+        manager.stop(id).await.unwrap();
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,54 @@
+use mcp_manager::{ApplyOptions, ManagerOptions, McpEvent, ToolInfo};
+use serde_json::json;
+
+#[test]
+fn options_round_trip_with_the_names_typescript_uses() {
+    let options = json!({
+        "logDir": "/var/log/mcp",
+        "maxLogFileSize": 1048576,
+        "maxLogFiles": 3,
+        "logTraffic": true,
+        "eventBufferSize": 100,
+        "serverEventBufferSize": 10,
+        "traceBufferSize": 50,
+        "redactionPatterns": ["sk-[a-z]+"],
+        "defaultRedactionPatterns": false,
+    });
+    let parsed: ManagerOptions = serde_json::from_value(options.clone()).unwrap();
+    assert_eq!(parsed.log_dir.as_deref(), Some("/var/log/mcp"));
+    assert_eq!(parsed.server_event_buffer_size, Some(10));
+    assert_eq!(parsed.default_redaction_patterns, Some(false));
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), options);
+
+    let apply = json!({ "concurrency": 2 });
+    let parsed: ApplyOptions = serde_json::from_value(apply.clone()).unwrap();
+    assert_eq!(parsed.concurrency, Some(2));
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), apply);
+}
+
+#[test]
+fn events_carry_tools_with_camel_case_fields() {
+    let tool = json!({
+        "serverId": "files",
+        "toolName": "read",
+        "qualifiedName": "files__read",
+        "description": "Read a file",
+        "parameters": "{}",
+        "annotations": {
+            "title": "Read",
+            "readOnlyHint": true,
+            "destructiveHint": false,
+            "idempotentHint": true,
+            "openWorldHint": null,
+        },
+    });
+    let parsed: ToolInfo = serde_json::from_value(tool.clone()).unwrap();
+    assert_eq!(parsed.qualified_name, "files__read");
+    assert_eq!(parsed.annotations.as_ref().unwrap().read_only_hint, Some(true));
+
+    let event = McpEvent::tools_updated("files", vec![parsed]);
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({ "eventType": "toolsUpdated", "serverId": "files", "tools": [tool], "sequence": 0 })
+    );
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::{manager, mock_server, result_text};
+use mcp_manager::{Error, McpEvent, McpManager, NamespacePolicy, ServerConfig};
+use serde_json::json;
+
+/// Server with a `lookup` tool answering with the server's ID
+fn lookup_server(id: &str) -> ServerConfig {
+    mock_server(id, json!({ "tools": [{ "name": "lookup", "text": id }] }))
+}
+
+/// Qualified names of all tools, in start order
+fn qualified_names(manager: &McpManager) -> Vec<String> {
+    manager.get_tools().into_iter().map(|t| t.qualified_name).collect()
+}
+
+/// Warnings about hidden tools emitted so far
+fn shadowing_warnings(manager: &McpManager) -> Vec<String> {
+    manager
+        .get_recent_events(&Default::default())
+        .into_iter()
+        .filter_map(|e| match e {
+            McpEvent::Log(log) if log.level == "warn" && log.message.contains("is hidden") => {
+                Some(log.message)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Qualified names of a server's tools in the latest `toolsUpdated` event about it
+fn updated_names(manager: &McpManager, server_id: &str) -> Vec<String> {
+    manager
+        .get_recent_events(&Default::default())
+        .into_iter()
+        .rev()
+        .find_map(|e| match e {
+            McpEvent::ToolsUpdated(event) if event.server_id == server_id => Some(event.tools),
+            _ => None,
+        })
+        .expect("no toolsUpdated event")
+        .into_iter()
+        .map(|t| t.qualified_name)
+        .collect()
+}
+
+#[tokio::test]
+async fn custom_prefixes_fall_back_to_the_server_id() {
+    let (manager, _events) = manager();
+    let mut prefixed = lookup_server("files");
+    prefixed.tool_prefix = Some("fs".to_string());
+    let mut bare = lookup_server("search");
+    bare.tool_prefix = Some(String::new());
+    manager.start(prefixed).await.unwrap();
+    manager.start(bare).await.unwrap();
+    manager.start(lookup_server("notes")).await.unwrap();
+    assert_eq!(
+        qualified_names(&manager),
+        ["files__lookup", "search__lookup", "notes__lookup"]
+    );
+
+    // Every server is told about its renamed tools
+    manager.set_namespace_policy(NamespacePolicy::CustomPrefix);
+    assert_eq!(manager.namespace_policy(), NamespacePolicy::CustomPrefix);
+    assert_eq!(updated_names(&manager, "files"), ["fs__lookup"]);
+    assert_eq!(updated_names(&manager, "search"), ["lookup"]);
+    assert_eq!(updated_names(&manager, "notes"), ["notes__lookup"]);
+    assert_eq!(qualified_names(&manager), ["fs__lookup", "lookup", "notes__lookup"]);
+
+    let result = manager
+        .execute_tool_by_qualified_name("fs__lookup", "{}".to_string())
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), "files");
+    let result = manager
+        .execute_tool_by_qualified_name("lookup", "{}".to_string())
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), "search");
+
+    // Names of another policy are unknown
+    let result = manager
+        .execute_tool_by_qualified_name("files__lookup", "{}".to_string())
+        .await;
+    assert!(matches!(result, Err(Error::ToolNotFound(_))), "{:?}", result);
+
+    for id in ["files", "search", "notes"] {
+        manager.stop(id).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn first_started_server_wins_colliding_names() {
+    let (manager, _events) = manager();
+    manager.set_namespace_policy(NamespacePolicy::FirstWins);
+    manager.start(lookup_server("first")).await.unwrap();
+    manager.start(lookup_server("second")).await.unwrap();
+
+    assert_eq!(qualified_names(&manager), ["lookup"]);
+    assert_eq!(
+        shadowing_warnings(&manager),
+        ["Tool 'lookup' is hidden because another server already provides 'lookup'"]
+    );
+
+    // The name both servers provide resolves to the server started first
+    let result = manager
+        .execute_tool_by_qualified_name("lookup", "{}".to_string())
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), "first");
+    let result = manager
+        .execute_tool_by_qualified_name("missing", "{}".to_string())
+        .await;
+    assert!(matches!(result, Err(Error::ToolNotFound(_))), "{:?}", result);
+
+    // Once the first server stops, the hidden tool takes the name over
+    manager.stop("first").await.unwrap();
+    assert_eq!(qualified_names(&manager), ["lookup"]);
+    let result = manager
+        .execute_tool_by_qualified_name("lookup", "{}".to_string())
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), "second");
+
+    manager.stop("second").await.unwrap();
+}
+
+#[tokio::test]
+async fn switching_policies_reports_newly_hidden_tools() {
+    let (manager, _events) = manager();
+    manager.start(lookup_server("first")).await.unwrap();
+    manager.start(lookup_server("second")).await.unwrap();
+    assert!(shadowing_warnings(&manager).is_empty());
+
+    manager.set_namespace_policy(NamespacePolicy::FirstWins);
+    assert_eq!(qualified_names(&manager), ["lookup"]);
+    assert_eq!(shadowing_warnings(&manager).len(), 1);
+    assert_eq!(updated_names(&manager, "first"), ["lookup"]);
+    assert!(updated_names(&manager, "second").is_empty());
+
+    // Setting the same policy again changes no names, so nothing is emitted
+    let emitted = manager.get_recent_events(&Default::default()).len();
+    manager.set_namespace_policy(NamespacePolicy::FirstWins);
+    assert_eq!(manager.get_recent_events(&Default::default()).len(), emitted);
+
+    manager.stop("first").await.unwrap();
+    manager.stop("second").await.unwrap();
+}
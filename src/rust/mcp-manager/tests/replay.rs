@@ -0,0 +1,65 @@
+mod common;
+
+use common::{call, manager, mock_server, result_text};
+use mcp_manager::{ReplayConfig, ReplayMatcher, ServerConfig};
+use serde_json::json;
+
+/// Server playing back a recording; the command is never run
+fn replayed(path: &str, matcher: ReplayMatcher, ignore_params: Option<Vec<String>>) -> ServerConfig {
+    ServerConfig {
+        replay: Some(ReplayConfig {
+            path: path.to_string(),
+            matcher: Some(matcher),
+            ignore_params,
+        }),
+        ..mock_server("search", json!({}))
+    }
+}
+
+#[tokio::test]
+async fn recorded_session_is_replayed() {
+    let path = std::env::temp_dir().join(format!("mcp-manager-replay-{}.jsonl", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    let first = r#"{"query":"rust","timestamp":1}"#;
+    let second = r#"{"query":"node","timestamp":2}"#;
+
+    let (manager, _events) = manager();
+    let mut config = mock_server("search", json!({ "tools": [{ "name": "lookup" }] }));
+    config.record = Some(path.clone());
+    manager.start(config).await.unwrap();
+    manager.execute_tool(call("search", "lookup", first)).await.unwrap();
+    manager.execute_tool(call("search", "lookup", second)).await.unwrap();
+    manager.stop("search").await.unwrap();
+
+    // Tools are listed and calls answered from the recording alone
+    manager
+        .start(replayed(&path, ReplayMatcher::Exact, None))
+        .await
+        .unwrap();
+    let tools: Vec<String> = manager.get_tools().into_iter().map(|t| t.tool_name).collect();
+    assert_eq!(tools, ["lookup"]);
+    let result = manager.execute_tool(call("search", "lookup", second)).await.unwrap();
+    assert_eq!(result_text(&result), second);
+    let result = manager.execute_tool(call("search", "lookup", first)).await.unwrap();
+    assert_eq!(result_text(&result), first);
+    assert!(manager
+        .execute_tool(call("search", "lookup", r#"{"query":"rust","timestamp":3}"#))
+        .await
+        .is_err());
+    manager.stop("search").await.unwrap();
+
+    // Ignored params do not take part in matching
+    let ignored = Some(vec!["/arguments/timestamp".to_string()]);
+    manager
+        .start(replayed(&path, ReplayMatcher::Exact, ignored))
+        .await
+        .unwrap();
+    let result = manager
+        .execute_tool(call("search", "lookup", r#"{"query":"rust","timestamp":3}"#))
+        .await
+        .unwrap();
+    assert_eq!(result_text(&result), first);
+    manager.stop("search").await.unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+}
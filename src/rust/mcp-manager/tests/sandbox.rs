@@ -0,0 +1,175 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::{manager, mock_server};
+use mcp_manager::{
+    Error, EventQuery, McpEvent, SandboxConfig, SandboxNetwork, ServerConfig, ToolExecutionRequest,
+};
+use serde_json::{json, Value};
+
+/// Server with a tool reading the file at the path it is named after
+fn file_reader(id: &str, paths: &[&str], sandbox: SandboxConfig) -> ServerConfig {
+    let tools: Vec<Value> = paths
+        .iter()
+        .map(|path| json!({ "name": path, "readFile": path }))
+        .collect();
+    let mut config = mock_server(id, json!({ "tools": tools }));
+    config.sandbox = Some(sandbox);
+    config
+}
+
+fn read(server_id: &str, path: &str) -> ToolExecutionRequest {
+    ToolExecutionRequest {
+        server_id: server_id.to_string(),
+        tool_name: path.to_string(),
+        inputs: "{}".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Text and error flag of a tool result
+fn outcome(result: &str) -> (String, bool) {
+    let result: Value = serde_json::from_str(result).unwrap();
+    (
+        result["content"][0]["text"].as_str().unwrap_or_default().to_string(),
+        result["isError"].as_bool().unwrap_or(false),
+    )
+}
+
+#[tokio::test]
+async fn resource_limits_and_no_new_privs_apply() {
+    let (manager, _events) = manager();
+    let sandbox = SandboxConfig {
+        max_memory_mb: Some(2048),
+        max_open_files: Some(64),
+        max_cpu_seconds: Some(600),
+        ..Default::default()
+    };
+    let paths = ["/proc/self/limits", "/proc/self/status"];
+    manager.start(file_reader("limited", &paths, sandbox)).await.unwrap();
+
+    let (limits, _) = outcome(&manager.execute_tool(read("limited", paths[0])).await.unwrap());
+    let limit = |name: &str| {
+        limits
+            .lines()
+            .find(|line| line.starts_with(name))
+            .unwrap_or_else(|| panic!("no '{}' limit in: {}", name, limits))
+            .split_whitespace()
+            .rev()
+            .skip(1)
+            .take(2)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(limit("Max open files"), ["64", "64"]);
+    assert_eq!(limit("Max address space"), ["2147483648", "2147483648"]);
+    assert_eq!(limit("Max cpu time"), ["600", "600"]);
+
+    let (status, _) = outcome(&manager.execute_tool(read("limited", paths[1])).await.unwrap());
+    assert!(status.lines().any(|line| line.split_whitespace().eq(["NoNewPrivs:", "1"])));
+
+    manager.stop("limited").await.unwrap();
+}
+
+#[tokio::test]
+async fn landlock_restricts_file_access() {
+    let (manager, _events) = manager();
+    let dir = std::env::temp_dir().join(format!("mcp-manager-sandbox-{}", std::process::id()));
+    let allowed = dir.join("allowed");
+    let denied = dir.join("denied");
+    std::fs::create_dir_all(&allowed).unwrap();
+    std::fs::create_dir_all(&denied).unwrap();
+    std::fs::write(allowed.join("file"), "allowed content").unwrap();
+    std::fs::write(denied.join("file"), "denied content").unwrap();
+
+    let allowed_file = allowed.join("file").to_string_lossy().into_owned();
+    let denied_file = denied.join("file").to_string_lossy().into_owned();
+    let sandbox = SandboxConfig {
+        read_paths: Some(vec![allowed.to_string_lossy().into_owned()]),
+        ..Default::default()
+    };
+    let config = file_reader("landlocked", &[&allowed_file, &denied_file], sandbox);
+    match manager.start(config).await {
+        Ok(_) => {}
+        Err(Error::ProcessStartError(message)) if message.contains("Landlock") => {
+            eprintln!("skipping, as this kernel lacks Landlock: {}", message);
+            std::fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+        Err(e) => panic!("failed to start: {}", e),
+    }
+
+    let result = manager.execute_tool(read("landlocked", &allowed_file)).await.unwrap();
+    assert_eq!(outcome(&result), ("allowed content".to_string(), false));
+    let (message, is_error) =
+        outcome(&manager.execute_tool(read("landlocked", &denied_file)).await.unwrap());
+    assert!(is_error);
+    assert!(message.contains("Permission denied"), "{}", message);
+
+    manager.stop("landlocked").await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn invalid_sandboxes_fail_the_start() {
+    let (manager, _events) = manager();
+
+    let sandbox = SandboxConfig {
+        max_open_files: Some(u32::MAX),
+        ..Default::default()
+    };
+    let result = manager.start(file_reader("too-many-files", &[], sandbox)).await;
+    assert!(
+        matches!(&result, Err(Error::ProcessStartError(message)) if message.contains("maxOpenFiles")),
+        "{:?}",
+        result.err()
+    );
+
+    let sandbox = SandboxConfig {
+        read_paths: Some(vec!["/does/not/exist".to_string()]),
+        ..Default::default()
+    };
+    let result = manager.start(file_reader("missing-path", &[], sandbox)).await;
+    assert!(matches!(result, Err(Error::ProcessStartError(_))));
+    assert!(manager.list_servers().is_empty());
+}
+
+#[tokio::test]
+async fn network_none_blocks_loopback_connections() {
+    let (manager, _events) = manager();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let script = json!({ "tools": [{ "name": "connect", "connect": address }] });
+    let connect = |server_id: &str| ToolExecutionRequest {
+        server_id: server_id.to_string(),
+        tool_name: "connect".to_string(),
+        inputs: "{}".to_string(),
+        ..Default::default()
+    };
+
+    manager.start(mock_server("online", script.clone())).await.unwrap();
+    let result = manager.execute_tool(connect("online")).await.unwrap();
+    assert_eq!(outcome(&result), ("connected".to_string(), false));
+
+    let mut config = mock_server("offline", script);
+    config.sandbox = Some(SandboxConfig {
+        network: Some(SandboxNetwork::None),
+        ..Default::default()
+    });
+    manager.start(config).await.unwrap();
+    let (message, is_error) = outcome(&manager.execute_tool(connect("offline")).await.unwrap());
+    let isolated = !manager
+        .get_recent_events(&EventQuery::default())
+        .iter()
+        .any(|e| matches!(e, McpEvent::Log(log) if log.message.contains("Network namespaces")));
+    if isolated {
+        assert!(is_error, "connected from inside the network namespace");
+        assert!(message.contains("unreachable"), "{}", message);
+    } else {
+        eprintln!("skipping, as namespaces are unavailable");
+        assert!(!is_error);
+    }
+
+    manager.stop("online").await.unwrap();
+    manager.stop("offline").await.unwrap();
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{manager, mock_server, result_text};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use mcp_manager::{
+    Error, FileSecretProvider, McpEvent, SecretProvider, ToolExecutionRequest, TraceQuery,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Secret value that must never show up outside the server process
+const SECRET: &str = "sk-test-4f9a1c2b7e";
+
+/// Provider answering every key with the same value
+struct StaticSecret;
+
+impl SecretProvider for StaticSecret {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn get(&self, _key: &str) -> BoxFuture<'static, mcp_manager::Result<String>> {
+        futures::future::ready(Ok(SECRET.to_string())).boxed()
+    }
+}
+
+fn read_env(server_id: &str) -> ToolExecutionRequest {
+    ToolExecutionRequest {
+        server_id: server_id.to_string(),
+        tool_name: "whoami".to_string(),
+        inputs: "{}".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn secret_references_are_resolved_and_masked() {
+    let (manager, mut events) = manager();
+    manager.set_secret_provider(Some(Arc::new(StaticSecret)));
+
+    let mut config = mock_server("secret", json!({ "tools": [{ "name": "whoami", "env": "API_KEY" }] }));
+    config.env = Some(HashMap::from([(
+        "API_KEY".to_string(),
+        "${secret:api-key}".to_string(),
+    )]));
+    config.trace = Some(true);
+    manager.start(config).await.unwrap();
+
+    // The server sees the value, but nothing the manager reports does
+    let result = manager.execute_tool(read_env("secret")).await.unwrap();
+    assert_eq!(result_text(&result), SECRET);
+
+    let event = events
+        .wait_for(|e| matches!(e, McpEvent::Log(log) if log.message.contains("API_KEY=")))
+        .await;
+    let McpEvent::Log(log) = event else {
+        unreachable!()
+    };
+    assert_eq!(log.message, "DEBUG API_KEY=[REDACTED]");
+
+    let trace = manager.get_trace("secret", &TraceQuery::default());
+    assert!(!trace.is_empty());
+    assert!(trace.iter().all(|entry| !entry.message.contains(SECRET)));
+
+    let servers = manager.list_servers();
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].config.env.as_ref().unwrap()["API_KEY"], "${secret:api-key}");
+
+    manager.stop("secret").await.unwrap();
+}
+
+#[tokio::test]
+async fn env_and_file_references_are_resolved() {
+    let (manager, _events) = manager();
+    let dir = std::env::temp_dir().join(format!("mcp-manager-secrets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let token = dir.join("token");
+    std::fs::write(&token, format!("{}\n", SECRET)).unwrap();
+    let secrets = dir.join("secrets.json");
+    std::fs::write(&secrets, json!({ "user": "octocat" }).to_string()).unwrap();
+    manager.set_secret_provider(Some(Arc::new(FileSecretProvider::new(&secrets))));
+
+    let mut config = mock_server("refs", json!({ "tools": [{ "name": "whoami", "env": "CREDENTIALS" }] }));
+    config.env = Some(HashMap::from([(
+        "CREDENTIALS".to_string(),
+        format!(
+            "${{secret:user}}:${{file:{}}}@${{env:CARGO_MANIFEST_DIR}}",
+            token.display()
+        ),
+    )]));
+    manager.start(config).await.unwrap();
+
+    let result = manager.execute_tool(read_env("refs")).await.unwrap();
+    assert_eq!(
+        result_text(&result),
+        format!("octocat:{}@{}", SECRET, env!("CARGO_MANIFEST_DIR"))
+    );
+
+    manager.stop("refs").await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn unresolvable_references_fail_the_start() {
+    let (manager, _events) = manager();
+    let mut config = mock_server("missing", json!({}));
+    config.env = Some(HashMap::from([(
+        "API_KEY".to_string(),
+        "${secret:api-key}".to_string(),
+    )]));
+
+    let result = manager.start(config.clone()).await;
+    assert!(matches!(result, Err(Error::ConfigError(message)) if message.contains("API_KEY")));
+
+    config.env = Some(HashMap::from([(
+        "API_KEY".to_string(),
+        "${env:MCP_MANAGER_TEST_UNSET_VARIABLE}".to_string(),
+    )]));
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+    assert!(manager.list_servers().is_empty());
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{call, manager, mock_server};
+use mcp_manager::{Error, McpEvent, McpManager, StatsWatchOptions};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn stats_cover_the_process_tree() {
+    let (manager, _events) = manager();
+    let manager = Arc::new(manager);
+    let script = json!({ "tools": [
+        { "name": "spawn", "spawn": ["sleep", "30"] },
+        { "name": "busy", "busyMs": 1500 },
+    ] });
+    manager.start(mock_server("measured", script)).await.unwrap();
+    let pid = manager.list_servers()[0].pid.unwrap();
+
+    let stats = manager.get_server_stats("measured").await.unwrap();
+    assert_eq!(stats.pid, pid);
+    assert_eq!(stats.child_count, 0);
+    assert!(stats.memory_bytes > 0.0);
+    assert!(stats.virtual_memory_bytes >= stats.memory_bytes);
+    assert!(stats.thread_count >= 1);
+    if cfg!(target_os = "linux") {
+        // stdin, stdout and stderr at least
+        assert!(stats.open_files.unwrap() >= 3);
+    }
+
+    manager.execute_tool(call("measured", "spawn", "{}")).await.unwrap();
+    let stats = manager.get_server_stats("measured").await.unwrap();
+    assert_eq!(stats.child_count, 1);
+
+    let busy = tokio::spawn({
+        let manager = manager.clone();
+        async move { manager.execute_tool(call("measured", "busy", "{}")).await }
+    });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let stats = manager.get_server_stats("measured").await.unwrap();
+    assert!(stats.cpu_percent > 20.0, "cpu usage {}", stats.cpu_percent);
+    busy.await.unwrap().unwrap();
+
+    manager.stop("measured").await.unwrap();
+}
+
+#[tokio::test]
+async fn watched_stats_are_emitted_periodically() {
+    let (manager, mut events) = manager();
+    let manager = Arc::new(manager);
+    manager.start(mock_server("first", json!({}))).await.unwrap();
+    manager.start(mock_server("second", json!({}))).await.unwrap();
+
+    manager.watch_stats(StatsWatchOptions {
+        interval_ms: Some(50),
+    });
+    for server_id in ["first", "second"] {
+        for _ in 0..2 {
+            let event = events
+                .wait_for(|e| matches!(e, McpEvent::ServerStats(stats) if stats.server_id == server_id))
+                .await;
+            let McpEvent::ServerStats(event) = event else {
+                unreachable!()
+            };
+            assert!(event.stats.memory_bytes > 0.0);
+        }
+    }
+
+    manager.unwatch_stats();
+    let emitted = stats_events(&manager);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(stats_events(&manager), emitted);
+
+    manager.stop("first").await.unwrap();
+    manager.stop("second").await.unwrap();
+}
+
+fn stats_events(manager: &McpManager) -> usize {
+    manager
+        .get_recent_events(&Default::default())
+        .iter()
+        .filter(|e| matches!(e, McpEvent::ServerStats(_)))
+        .count()
+}
+
+#[tokio::test]
+async fn stats_of_unknown_servers_fail() {
+    let (manager, _events) = manager();
+    let result = manager.get_server_stats("missing").await;
+    assert!(
+        matches!(&result, Err(Error::ServerNotFound(message)) if message == "Server with ID 'missing' not found"),
+        "{:?}",
+        result
+    );
+}
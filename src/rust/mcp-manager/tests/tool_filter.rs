@@ -0,0 +1,89 @@
+mod common;
+
+use common::{call, manager, mock_server, result_text};
+use mcp_manager::{Error, McpManager, ServerConfig};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Server with tools answering with their own name
+fn named_tools(id: &str, names: &[&str]) -> ServerConfig {
+    let tools: Vec<_> = names
+        .iter()
+        .map(|name| json!({ "name": name, "text": name }))
+        .collect();
+    mock_server(id, json!({ "tools": tools }))
+}
+
+fn tool_names(manager: &McpManager) -> Vec<String> {
+    let mut names: Vec<String> = manager.get_tools().into_iter().map(|t| t.tool_name).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn excluded_tools_are_hidden_and_cannot_be_called() {
+    let (manager, _events) = manager();
+    let mut config = named_tools("files", &["read_file", "write_file", "delete_file", "stat"]);
+    config.include_tools = Some(vec!["*_file".to_string()]);
+    config.exclude_tools = Some(vec!["delete_*".to_string()]);
+    manager.start(config).await.unwrap();
+
+    assert_eq!(tool_names(&manager), ["read_file", "write_file"]);
+    let result = manager.execute_tool(call("files", "read_file", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "read_file");
+    for hidden in ["delete_file", "stat"] {
+        let result = manager.execute_tool(call("files", hidden, "{}")).await;
+        assert!(matches!(result, Err(Error::ToolNotFound(_))), "{}: {:?}", hidden, result);
+    }
+
+    manager.stop("files").await.unwrap();
+}
+
+#[tokio::test]
+async fn aliased_tools_are_called_by_their_alias() {
+    let (manager, _events) = manager();
+    let mut config = named_tools("search", &["query", "fetch"]);
+    config.aliases = Some(HashMap::from([
+        ("query".to_string(), "search_web".to_string()),
+        // Swapping names is fine, as neither keeps its own
+        ("fetch".to_string(), "query".to_string()),
+    ]));
+    manager.start(config).await.unwrap();
+
+    assert_eq!(tool_names(&manager), ["query", "search_web"]);
+    let result = manager.execute_tool(call("search", "search_web", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "query");
+    let result = manager.execute_tool(call("search", "query", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "fetch");
+
+    manager.stop("search").await.unwrap();
+}
+
+#[tokio::test]
+async fn aliases_taking_an_exposed_name_are_rejected() {
+    let (manager, _events) = manager();
+
+    let mut config = named_tools("taken", &["query", "fetch"]);
+    config.aliases = Some(HashMap::from([("fetch".to_string(), "query".to_string())]));
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))), "{:?}", result);
+
+    let mut config = named_tools("twice", &["query", "fetch"]);
+    config.aliases = Some(HashMap::from([
+        ("query".to_string(), "lookup".to_string()),
+        ("fetch".to_string(), "lookup".to_string()),
+    ]));
+    let result = manager.start(config).await;
+    assert!(matches!(result, Err(Error::ConfigError(_))), "{:?}", result);
+    assert!(manager.list_servers().is_empty());
+
+    // A hidden tool does not hold on to its name
+    let mut config = named_tools("hidden", &["query", "fetch"]);
+    config.exclude_tools = Some(vec!["query".to_string()]);
+    config.aliases = Some(HashMap::from([("fetch".to_string(), "query".to_string())]));
+    manager.start(config).await.unwrap();
+    let result = manager.execute_tool(call("hidden", "query", "{}")).await.unwrap();
+    assert_eq!(result_text(&result), "fetch");
+
+    manager.stop("hidden").await.unwrap();
+}
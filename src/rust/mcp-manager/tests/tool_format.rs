@@ -0,0 +1,132 @@
+mod common;
+
+use common::{manager, mock_server};
+use mcp_manager::{ProviderTool, ToolFormat};
+use serde_json::{Value, json};
+
+/// Definition of the exported tool with the given name
+fn definition(tools: &[ProviderTool], name: &str) -> Value {
+    let tool = tools
+        .iter()
+        .find(|tool| tool.name == name)
+        .unwrap_or_else(|| panic!("no tool named '{}' in {:?}", name, tools));
+    serde_json::from_str(&tool.definition).unwrap()
+}
+
+#[tokio::test]
+async fn tools_are_exported_per_provider() {
+    let (manager, _events) = manager();
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "properties": {
+            "path": { "$ref": "#/$defs/path" },
+            "mode": { "type": ["string", "null"], "format": "mode" },
+        },
+        "required": ["path"],
+        "$defs": { "path": { "type": "string", "description": "A path" } },
+    });
+    let script = json!({ "tools": [
+        { "name": "read", "description": "Read a file", "inputSchema": schema },
+    ] });
+    manager.start(mock_server("files", script)).await.unwrap();
+
+    let openai = manager.get_tools_as(ToolFormat::OpenAI);
+    assert_eq!(openai.len(), 1);
+    assert_eq!((openai[0].server_id.as_str(), openai[0].tool_name.as_str()), ("files", "read"));
+    let function = &definition(&openai, "files__read")["function"];
+    assert_eq!(function["description"], "Read a file");
+    assert_eq!(
+        function["parameters"],
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "A path" },
+                "mode": { "type": ["string", "null"], "format": "mode" },
+            },
+            "required": ["path"],
+        })
+    );
+
+    let anthropic = manager.get_tools_as(ToolFormat::Anthropic);
+    let tool = definition(&anthropic, "files__read");
+    assert_eq!(tool["input_schema"], function["parameters"]);
+
+    let gemini = manager.get_tools_as(ToolFormat::Gemini);
+    let mode = &definition(&gemini, "files__read")["parameters"]["properties"]["mode"];
+    assert_eq!(*mode, json!({ "type": "string", "nullable": true }));
+
+    let resolved = manager.resolve_tool_name("files__read").unwrap();
+    assert_eq!(resolved.tool_name, "read");
+
+    manager.stop("files").await.unwrap();
+}
+
+#[tokio::test]
+async fn properties_named_like_meta_keywords_are_kept() {
+    let (manager, _events) = manager();
+    let schema = json!({
+        "type": "object",
+        "$id": "urn:example:schema",
+        "properties": {
+            "definitions": { "type": "string", "$comment": "dropped" },
+            "$id": { "type": "string" },
+            "examples": {
+                "type": "object",
+                "properties": { "$schema": { "type": "boolean" } },
+            },
+        },
+    });
+    let script = json!({ "tools": [{ "name": "meta", "inputSchema": schema }] });
+    manager.start(mock_server("meta", script)).await.unwrap();
+
+    let tools = manager.get_tools_as(ToolFormat::Anthropic);
+    let parameters = &definition(&tools, "meta__meta")["input_schema"];
+    assert_eq!(
+        *parameters,
+        json!({
+            "type": "object",
+            "properties": {
+                "definitions": { "type": "string" },
+                "$id": { "type": "string" },
+                "examples": {
+                    "type": "object",
+                    "properties": { "$schema": { "type": "boolean" } },
+                },
+            },
+        })
+    );
+
+    manager.stop("meta").await.unwrap();
+}
+
+#[tokio::test]
+async fn mangled_names_are_unique() {
+    let (manager, _events) = manager();
+    let long_name = "x".repeat(80);
+    // `a.b` and `a_b` sanitize alike and get hash suffixes, one of which a third tool uses;
+    // the suffixes hash the server ID and tool name, so they stay the same across builds
+    let taken = "a_b_1a1e3b64";
+    let script = json!({ "tools": [
+        { "name": "a.b" },
+        { "name": "a_b" },
+        { "name": taken },
+        { "name": long_name },
+    ] });
+    manager.start(mock_server("names", script)).await.unwrap();
+
+    let tools = manager.get_tools_as(ToolFormat::OpenAI);
+    let mut names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert!(names.contains(&format!("names__{}", taken).as_str()), "{:?}", names);
+    assert!(names.iter().all(|name| name.len() <= 64), "{:?}", names);
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 4, "{:?}", tools);
+
+    for tool in &tools {
+        let resolved = manager.resolve_tool_name(&tool.name).unwrap();
+        assert_eq!(resolved.tool_name, tool.tool_name);
+    }
+
+    manager.stop("names").await.unwrap();
+}
@@ -0,0 +1,40 @@
+use mcp_manager::McpEvent;
+use std::fs;
+
+const INDEX_D_TS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/index.d.ts");
+
+/// Declarations NAPI-RS leaves out of `index.d.ts`, with what follows them
+fn with_type_defs(checked_in: &str) -> String {
+    let union = McpEvent::type_def();
+    let Some(start) = checked_in.find("/**\n * Event emitted by the MCP manager\n") else {
+        return format!("{}{}", checked_in, union);
+    };
+    let declaration = start + checked_in[start..].find("export type McpEvent =\n").unwrap();
+    let mut end = declaration + checked_in[declaration..].find('\n').unwrap() + 1;
+    while checked_in[end..].starts_with("  | ") {
+        end += checked_in[end..].find('\n').unwrap() + 1;
+    }
+    // Older files may lack the declarations following the union
+    let discriminant = &union[union.rfind("/**").unwrap()..];
+    if checked_in[end..].starts_with(discriminant) {
+        end += discriminant.len();
+    }
+    format!("{}{}{}", &checked_in[..start], union, &checked_in[end..])
+}
+
+#[test]
+fn checked_in_type_defs_are_up_to_date() {
+    let checked_in = fs::read_to_string(INDEX_D_TS).unwrap();
+    assert!(
+        checked_in == with_type_defs(&checked_in),
+        "index.d.ts is out of date; run `npm run build:types`"
+    );
+}
+
+/// Run after `napi build` has regenerated `index.d.ts`
+#[test]
+#[ignore = "rewrites index.d.ts; run by `npm run build:types`"]
+fn write_type_defs() {
+    let checked_in = fs::read_to_string(INDEX_D_TS).unwrap();
+    fs::write(INDEX_D_TS, with_type_defs(&checked_in)).unwrap();
+}